@@ -1,16 +1,18 @@
 COPY	START	1000
 FIRST	STL	RETADR
-CLOOP	JSUB	RDREC
+	LDB	#EOF
+	BASE	EOF
+CLOOP	+JSUB	RDREC
 	LDA	LENGTH
 	COMP	ZERO
 	JEQ	ENDFIL
-	JSUB	WRREC
+	+JSUB	WRREC
 	J	CLOOP
 ENDFIL	LDA	EOF
 	STA	BUFFER
 	LDA	THREE
 	STA	LENGTH
-	JSUB	WRREC
+	+JSUB	WRREC
 	LDL	RETADR
 	.RSUB
 halt	J	halt
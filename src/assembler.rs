@@ -0,0 +1,89 @@
+use crate::diagnostic::Diagnostics;
+use crate::lexer::SourceFormat;
+use crate::listing::listing;
+use crate::messages::Lang;
+use crate::object::ObjectProgram;
+use crate::optables::Optab;
+use crate::pass1::Pass1;
+use crate::pass2::pass2;
+
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Assemble as if the program said `START 0`, for relocating loaders.
+    pub relocatable: bool,
+    /// Name the control section in M records (`M00000405+COPY`).
+    pub symbolic_modification: bool,
+    pub source_format: SourceFormat,
+}
+
+/// A two-pass SIC/XE assembler.
+pub struct Assembler {
+    options: Options,
+    optab: Optab,
+}
+
+impl Assembler {
+    pub fn new(options: Options) -> Self {
+        Assembler {
+            options,
+            optab: Optab::new(),
+        }
+    }
+
+    pub fn optab(&self) -> &Optab {
+        &self.optab
+    }
+
+    /// Runs both passes over `source`. Fails with every diagnostic when
+    /// there is at least one error; warnings otherwise end up in the
+    /// program.
+    pub fn assemble(&self, source: &str) -> Result<ObjectProgram, Diagnostics> {
+        let (mut program, diagnostics) = self.run(source);
+        if diagnostics.has_errors() {
+            return Err(diagnostics);
+        }
+        program.warnings = diagnostics;
+        Ok(program)
+    }
+
+    /// Runs both passes over `source` whatever goes wrong, returning the
+    /// program without the lines in error alongside the diagnostics.
+    pub fn run(&self, source: &str) -> (ObjectProgram, Diagnostics) {
+        let mut diagnostics = Diagnostics::new();
+        let mut pass1 = Pass1::new(&self.options, &self.optab);
+        for (index, line) in source.lines().enumerate() {
+            if let Err(diagnostic) = pass1.line(index + 1, line, &mut diagnostics) {
+                diagnostics.push(diagnostic);
+            }
+        }
+        let program = pass2(
+            pass1.finish(&mut diagnostics),
+            &self.options,
+            &mut diagnostics,
+        );
+        (program, diagnostics)
+    }
+
+    /// The assembly listing of a `run` of `source`.
+    pub fn listing(
+        &self,
+        source: &str,
+        program: &ObjectProgram,
+        diagnostics: &Diagnostics,
+        lang: Lang,
+    ) -> String {
+        listing(
+            source,
+            program,
+            diagnostics,
+            &self.optab,
+            self.options.source_format,
+            lang,
+        )
+    }
+}
+
+/// Assembles `source` with a one-off `Assembler`.
+pub fn assemble(source: &str, options: Options) -> Result<ObjectProgram, Diagnostics> {
+    Assembler::new(options).assemble(source)
+}
@@ -0,0 +1,88 @@
+/// A program block introduced by `USE`. Block 0 is the unnamed default block.
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub name: String,
+    /// The saved location counter while another block is in use.
+    pub locctr: u32,
+    /// The highest location reached, which `ORG` can move back from.
+    pub length: u32,
+    pub start: u32,
+}
+
+#[derive(Debug)]
+pub struct BlockTable {
+    blocks: Vec<Block>,
+    current: usize,
+}
+
+impl Default for BlockTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockTable {
+    pub fn new() -> Self {
+        BlockTable {
+            blocks: vec![Block {
+                name: String::new(),
+                locctr: 0,
+                length: 0,
+                start: 0,
+            }],
+            current: 0,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Saves `locctr` for the current block and switches to `name`
+    /// (the default block when empty), loading its location counter.
+    pub fn switch(&mut self, name: &str, locctr: &mut u32) {
+        let index = match self.blocks.iter().position(|b| b.name == name) {
+            Some(index) => index,
+            None => {
+                self.blocks.push(Block {
+                    name: name.to_string(),
+                    locctr: 0,
+                    length: 0,
+                    start: 0,
+                });
+                self.blocks.len() - 1
+            }
+        };
+        self.enter(index, locctr);
+    }
+
+    /// Like `switch`, for a block known by its index.
+    pub fn enter(&mut self, index: usize, locctr: &mut u32) {
+        self.mark(*locctr);
+        self.blocks[self.current].locctr = *locctr;
+        self.current = index;
+        *locctr = self.blocks[index].locctr;
+    }
+
+    /// Records that the current block reaches at least `locctr`.
+    pub fn mark(&mut self, locctr: u32) {
+        let block = &mut self.blocks[self.current];
+        block.length = block.length.max(locctr);
+    }
+
+    /// Ends pass 1: lays the blocks out one after another from `start`
+    /// and returns the total program length.
+    pub fn finish(&mut self, locctr: u32, start: u32) -> u32 {
+        self.mark(locctr);
+        let mut address = start;
+        for block in self.blocks.iter_mut() {
+            block.start = address;
+            address = address.saturating_add(block.length);
+        }
+        address - start
+    }
+
+    pub fn starts(&self) -> Vec<u32> {
+        self.blocks.iter().map(|b| b.start).collect()
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::AsmError;
+use crate::expr::evaluate;
+use crate::symtab::SymbolTable;
+
+/// Decodes a `BYTE` operand (`C'..'` or `X'..'`) into its bytes.
+/// Inside `C'..'` a doubled quote (`C'IT''S'`) stands for one quote.
+pub fn byte_constant(operand: &str) -> Result<Vec<u8>, AsmError> {
+    let upper = operand.get(..2).map(str::to_uppercase);
+    if matches!(upper.as_deref(), Some("C'" | "X'"))
+        && (operand.len() < 3 || !operand.ends_with('\''))
+    {
+        return Err(AsmError::UnterminatedConstant(operand.to_string()));
+    }
+    if let Some(chars) = quoted(operand, 'C') {
+        if chars.is_empty() {
+            return Err(AsmError::EmptyConstant(operand.to_string()));
+        }
+        if chars.replace("''", "").contains('\'') {
+            return Err(AsmError::UnpairedQuote(operand.to_string()));
+        }
+        if let Some(c) = chars
+            .chars()
+            .find(|&c| !c.is_ascii() || (c.is_ascii_control() && c != '\t'))
+        {
+            return Err(AsmError::InvalidCharacter(c, operand.to_string()));
+        }
+        Ok(chars.replace("''", "'").bytes().collect())
+    } else if let Some(hex) = quoted(operand, 'X') {
+        if hex.is_empty() {
+            return Err(AsmError::EmptyConstant(operand.to_string()));
+        }
+        if let Some(c) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(AsmError::InvalidHexDigit(c, operand.to_string()));
+        }
+        if hex.len() % 2 != 0 {
+            return Err(AsmError::OddHexDigits(operand.to_string()));
+        }
+        Ok((0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect())
+    } else {
+        Err(AsmError::InvalidByteConstant(operand.to_string()))
+    }
+}
+
+/// Decodes a literal operand (`=C'..'`, `=X'..'` or `=value`) into its bytes.
+/// A numeric literal occupies one word.
+pub fn literal_constant(literal: &str) -> Result<Vec<u8>, AsmError> {
+    let constant = literal
+        .strip_prefix('=')
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| AsmError::InvalidLiteral(literal.to_string()))?;
+    if is_byte_literal(literal) {
+        return byte_constant(constant);
+    }
+    let value = word_value(evaluate(constant, &SymbolTable::new(), 0, 0)?.value)?;
+    Ok(value.to_be_bytes()[1..].to_vec())
+}
+
+/// Whether `literal` is `=C'..'` or `=X'..'`, in either case.
+pub fn is_byte_literal(literal: &str) -> bool {
+    let upper = literal.get(..3).map(str::to_uppercase);
+    matches!(upper.as_deref(), Some("=C'" | "=X'"))
+}
+
+/// Parses a numeric operand. `X'..'` and `0x..` are always hexadecimal;
+/// plain digits are read in `radix` (16 for START, 10 everywhere else).
+pub fn parse_number(text: &str, radix: u32) -> Result<u32, AsmError> {
+    let (digits, radix) = if let Some(hex) = quoted(text, 'X') {
+        (hex, 16)
+    } else if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        (hex, 16)
+    } else {
+        (text, radix)
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(AsmError::InvalidNumber {
+            text: text.to_string(),
+            radix,
+        });
+    }
+    u32::from_str_radix(digits, radix).map_err(|_| AsmError::NumberTooLarge(text.to_string()))
+}
+
+/// Checks that a value fits in a 24-bit word and returns its
+/// two's complement representation.
+pub fn word_value(value: i64) -> Result<u32, AsmError> {
+    if !(-(1 << 23)..(1 << 24)).contains(&value) {
+        return Err(AsmError::WordOutOfRange(value));
+    }
+    Ok(value as u32 & 0xFFFFFF)
+}
+
+/// The text between the quotes of `C'..'` or `X'..'`, in either case.
+fn quoted(operand: &str, prefix: char) -> Option<&str> {
+    operand
+        .strip_prefix([prefix, prefix.to_ascii_lowercase()])?
+        .strip_prefix('\'')?
+        .strip_suffix('\'')
+}
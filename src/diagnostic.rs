@@ -0,0 +1,162 @@
+use crate::error::AsmError;
+use crate::messages::Lang;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn message_id(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// The columns (1-based, `len` bytes long) of the offending source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    pub column: usize,
+    pub len: usize,
+}
+
+/// An `AsmError` together with where it happened. `line` is 0 for
+/// problems with the program as a whole.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub span: Option<Span>,
+    pub error: AsmError,
+}
+
+impl Diagnostic {
+    pub fn error(line: usize, error: AsmError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            line,
+            span: None,
+            error,
+        }
+    }
+
+    pub fn warning(line: usize, error: AsmError) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(line, error)
+        }
+    }
+
+    pub fn at(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Renders the diagnostic compiler-style: the message, its location
+    /// in `file`, the source line and a caret under the offending text.
+    pub fn render(&self, file: &str, source: &[&str], lang: Lang) -> String {
+        let mut out = format!(
+            "{}: {}\n",
+            lang.text(self.severity.message_id(), &[]),
+            lang.message(&self.error)
+        );
+        let location = match (self.line, self.span) {
+            (0, _) => file.to_string(),
+            (line, None) => format!("{}:{}", file, line),
+            (line, Some(span)) => format!("{}:{}:{}", file, line, span.column),
+        };
+        let text = self.line.checked_sub(1).and_then(|i| source.get(i));
+        let gutter = " ".repeat(self.line.to_string().len());
+        let _ = writeln!(out, "{}--> {}", gutter, location);
+        if let Some(text) = text {
+            let _ = writeln!(out, "{} |", gutter);
+            let _ = writeln!(out, "{} | {}", self.line, text.trim_end());
+            if let Some(span) = self.span {
+                // Keep tabs so the caret lines up however they are displayed.
+                let indent: String = text
+                    .get(..span.column - 1)
+                    .unwrap_or("")
+                    .chars()
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                let _ = writeln!(
+                    out,
+                    "{} | {}{}",
+                    gutter,
+                    indent,
+                    "^".repeat(span.len.max(1))
+                );
+            }
+        }
+        if let Some(help) = lang.help(&self.error) {
+            let _ = writeln!(out, "{} = {}: {}", gutter, lang.text("help", &[]), help);
+        }
+        out
+    }
+}
+
+/// Errors and warnings collected from both passes, so that one run
+/// reports every problem instead of stopping at the first.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.items.push(diagnostic);
+    }
+
+    pub fn error(&mut self, line: usize, error: AsmError) {
+        self.push(Diagnostic::error(line, error));
+    }
+
+    pub fn warning(&mut self, line: usize, error: AsmError) {
+        self.push(Diagnostic::warning(line, error));
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.items.iter().filter(|d| d.severity == severity).count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// The diagnostics in source order; pass 2 problems are found after
+    /// all of pass 1's, so insertion order alone would interleave them.
+    pub fn sorted(&self) -> Vec<&Diagnostic> {
+        let mut sorted: Vec<&Diagnostic> = self.items.iter().collect();
+        sorted.sort_by_key(|d| (d.line, d.span));
+        sorted
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Prints every diagnostic against `file`'s `source` to stderr,
+    /// followed by a summary line.
+    pub fn report(&self, file: &str, source: &str, lang: Lang) {
+        let lines: Vec<&str> = source.lines().collect();
+        for diagnostic in self.sorted() {
+            eprintln!("{}", diagnostic.render(file, &lines, lang));
+        }
+        let errors = self.count(Severity::Error);
+        let warnings = self.count(Severity::Warning);
+        if errors + warnings > 0 {
+            eprintln!(
+                "{}",
+                lang.text("summary", &[errors.to_string(), warnings.to_string()])
+            );
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::messages::Lang;
+use std::fmt;
+
+/// Everything that can go wrong while assembling a program.
+/// Where it happened is kept by the `Diagnostic` wrapping it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsmError {
+    // Source lines
+    MalformedLine,
+    InvalidOpcode {
+        opcode: String,
+        suggestion: Option<String>,
+    },
+    ExtendedFormat(String),
+    MissingLabel(String),
+    InvalidIndexRegister(String),
+    IndexedImmediate,
+    AddressingPrefix(String),
+    LiteralNotAllowed(String),
+    SymbolListExpected(String),
+    MissingOperand(String),
+    // Constants and numbers
+    UnterminatedConstant(String),
+    EmptyConstant(String),
+    UnpairedQuote(String),
+    InvalidCharacter(char, String),
+    InvalidHexDigit(char, String),
+    OddHexDigits(String),
+    InvalidByteConstant(String),
+    InvalidLiteral(String),
+    InvalidNumber {
+        text: String,
+        radix: u32,
+    },
+    NumberTooLarge(String),
+    WordOutOfRange(i64),
+    // Expressions and symbols
+    UnexpectedCharacter(char, String),
+    MissingParen(String),
+    IncompleteExpression(String),
+    IllegalRelative(String),
+    RelativeAcrossBlocks(String),
+    RelativeProduct(String),
+    ExternalProduct(String),
+    DivisionByZero(String),
+    ExpressionOverflow(String),
+    UndefinedSymbol(String),
+    DuplicateSymbol(String),
+    EquExternal(String),
+    OrgForwardReference(Box<AsmError>),
+    OrgWithoutPrevious,
+    OrgBeforeStart(String),
+    EndExternal(String),
+    UndefinedExtdef {
+        name: String,
+        section: String,
+    },
+    // Object code
+    ExternalNeedsFormat4(String),
+    DisplacementOutOfRange,
+    ValueOutOfRange {
+        value: i64,
+        format: u8,
+    },
+    MissingRegister(String),
+    UnknownRegister(String),
+    MissingNumber(String),
+    NumberOutOfRange {
+        text: String,
+        min: u8,
+        max: u8,
+    },
+    SingleOperand(String),
+    AddressOutOfRange,
+    // Whole program
+    NoStart,
+    NoEnd,
+    // Cross reference
+    UnusedSymbol(String),
+}
+
+impl AsmError {
+    /// The message catalog id of the error and the arguments its
+    /// translations are filled in with.
+    pub fn parts(&self) -> (&'static str, Vec<String>) {
+        use AsmError::*;
+        let one = |s: &str| vec![s.to_string()];
+        match self {
+            MalformedLine => ("malformed-line", vec![]),
+            InvalidOpcode { opcode, .. } => ("invalid-opcode", one(opcode)),
+            ExtendedFormat(op) => ("extended-format", one(op)),
+            MissingLabel(op) => ("missing-label", one(op)),
+            InvalidIndexRegister(r) => ("invalid-index-register", one(r)),
+            IndexedImmediate => ("indexed-immediate", vec![]),
+            AddressingPrefix(op) => ("addressing-prefix", one(op)),
+            LiteralNotAllowed(l) => ("literal-not-allowed", one(l)),
+            SymbolListExpected(op) => ("symbol-list-expected", one(op)),
+            MissingOperand(op) => ("missing-operand", one(op)),
+            UnterminatedConstant(c) => ("unterminated-constant", one(c)),
+            EmptyConstant(c) => ("empty-constant", one(c)),
+            UnpairedQuote(c) => ("unpaired-quote", one(c)),
+            InvalidCharacter(ch, c) => ("invalid-character", vec![format!("{:?}", ch), c.clone()]),
+            InvalidHexDigit(ch, c) => ("invalid-hex-digit", vec![format!("{:?}", ch), c.clone()]),
+            OddHexDigits(c) => ("odd-hex-digits", one(c)),
+            InvalidByteConstant(c) => ("invalid-byte-constant", one(c)),
+            InvalidLiteral(l) => ("invalid-literal", one(l)),
+            InvalidNumber { text, radix: 16 } => {
+                ("invalid-hex-number", one(&format!("{:?}", text)))
+            }
+            InvalidNumber { text, .. } => ("invalid-decimal-number", one(&format!("{:?}", text))),
+            NumberTooLarge(n) => ("number-too-large", one(n)),
+            WordOutOfRange(v) => ("word-out-of-range", vec![v.to_string()]),
+            UnexpectedCharacter(ch, e) => {
+                ("unexpected-character", vec![format!("{:?}", ch), e.clone()])
+            }
+            MissingParen(e) => ("missing-paren", one(e)),
+            IncompleteExpression(e) => ("incomplete-expression", one(e)),
+            IllegalRelative(e) => ("illegal-relative", one(e)),
+            RelativeAcrossBlocks(e) => ("relative-across-blocks", one(e)),
+            RelativeProduct(e) => ("relative-product", one(e)),
+            ExternalProduct(e) => ("external-product", one(e)),
+            DivisionByZero(e) => ("division-by-zero", one(e)),
+            ExpressionOverflow(e) => ("expression-overflow", one(e)),
+            UndefinedSymbol(s) => ("undefined-symbol", one(s)),
+            DuplicateSymbol(s) => ("duplicate-symbol", one(s)),
+            EquExternal(e) => ("equ-external", one(e)),
+            OrgForwardReference(e) => ("org-forward-reference", vec![e.to_string()]),
+            OrgWithoutPrevious => ("org-without-previous", vec![]),
+            OrgBeforeStart(s) => ("org-before-start", one(s)),
+            EndExternal(s) => ("end-external", one(s)),
+            UndefinedExtdef { name, section } => {
+                ("undefined-extdef", vec![name.clone(), section.clone()])
+            }
+            ExternalNeedsFormat4(s) => ("external-needs-format4", one(s)),
+            DisplacementOutOfRange => ("displacement-out-of-range", vec![]),
+            ValueOutOfRange { value, format } => (
+                "value-out-of-range",
+                vec![value.to_string(), format.to_string()],
+            ),
+            MissingRegister(op) => ("missing-register", one(op)),
+            UnknownRegister(r) => ("unknown-register", one(r)),
+            MissingNumber(op) => ("missing-number", one(op)),
+            NumberOutOfRange { text, min, max } => (
+                "number-out-of-range",
+                vec![text.clone(), min.to_string(), max.to_string()],
+            ),
+            SingleOperand(op) => ("single-operand", one(op)),
+            AddressOutOfRange => ("address-out-of-range", vec![]),
+            NoStart => ("no-start", vec![]),
+            NoEnd => ("no-end", vec![]),
+            UnusedSymbol(s) => ("unused-symbol", one(s)),
+        }
+    }
+}
+
+/// The English message; `Lang::message` gives the others.
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Lang::En.message(self))
+    }
+}
@@ -0,0 +1,273 @@
+use crate::constant::parse_number;
+use crate::error::AsmError;
+use crate::symtab::{SymbolKind, SymbolTable};
+
+/// Result of an expression: its value and whether it is relocatable.
+#[derive(Debug, Clone)]
+pub struct Value {
+    pub value: i64,
+    pub kind: SymbolKind,
+    pub block: usize,
+    /// Whether relative terms come from more than one program block.
+    /// Before the blocks are laid out their difference is meaningless.
+    pub spans_blocks: bool,
+    /// External symbols the value depends on, with their sign (`+`/`-`).
+    /// They contribute 0 to `value` and are left for the loader.
+    pub externals: Vec<(char, String)>,
+}
+
+/// Evaluates `+ - * /` expressions over numbers, symbols, parentheses
+/// and `*` (the current LOCCTR within `block`).
+///
+/// Relative terms must pair up (`BUFEND-BUFFER` is absolute) and may not
+/// be multiplied or divided; anything left over must be absolute or a
+/// single relative term.
+pub fn evaluate(
+    expr: &str,
+    symbol_table: &SymbolTable,
+    locctr: u32,
+    block: usize,
+) -> Result<Value, AsmError> {
+    let mut parser = Parser {
+        expr,
+        chars: expr.char_indices().peekable(),
+        symbol_table,
+        locctr,
+        block,
+        relative_blocks: Vec::new(),
+    };
+    let term = parser.expression()?;
+    if let Some((_, c)) = parser.chars.peek() {
+        return Err(AsmError::UnexpectedCharacter(*c, expr.to_string()));
+    }
+    let kind = match term.relative {
+        0 => SymbolKind::Absolute,
+        1 => SymbolKind::Relative,
+        _ => return Err(AsmError::IllegalRelative(expr.to_string())),
+    };
+    Ok(Value {
+        value: term.value,
+        kind,
+        block: parser.relative_blocks.last().copied().unwrap_or(block),
+        spans_blocks: parser
+            .relative_blocks
+            .iter()
+            .any(|&b| b != parser.relative_blocks[0]),
+        externals: term
+            .externals
+            .into_iter()
+            .map(|(sign, name)| (if sign > 0 { '+' } else { '-' }, name))
+            .collect(),
+    })
+}
+
+/// The symbol names an expression refers to, in order, without
+/// evaluating it. Numbers and `X'..'` constants are skipped.
+pub fn symbols(expr: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut chars = expr.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if !(c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        if matches!(c, 'X' | 'x') && matches!(chars.peek(), Some(&(_, '\''))) {
+            chars.next();
+            chars.find(|&(_, c)| c == '\'');
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(i, c)) = chars.peek() {
+            if !(c.is_ascii_alphanumeric() || c == '_') {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        if !c.is_ascii_digit() {
+            names.push(&expr[start..end]);
+        }
+    }
+    names
+}
+
+struct Parser<'a> {
+    expr: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    symbol_table: &'a SymbolTable,
+    locctr: u32,
+    block: usize,
+    /// The block of every relative term read so far.
+    relative_blocks: Vec<usize>,
+}
+
+/// A partial result: the value, its relative term count
+/// (+1 for each added relative term, -1 for each subtracted one)
+/// and the signed external references.
+#[derive(Default)]
+struct Term {
+    value: i64,
+    relative: i32,
+    externals: Vec<(i32, String)>,
+}
+
+impl Term {
+    fn absolute(value: i64) -> Self {
+        Term {
+            value,
+            ..Default::default()
+        }
+    }
+
+    fn negate(mut self) -> Option<Self> {
+        self.value = self.value.checked_neg()?;
+        self.relative = -self.relative;
+        for (sign, _) in self.externals.iter_mut() {
+            *sign = -*sign;
+        }
+        Some(self)
+    }
+
+    fn add(&mut self, rhs: Term) -> Option<()> {
+        self.value = self.value.checked_add(rhs.value)?;
+        self.relative += rhs.relative;
+        self.externals.extend(rhs.externals);
+        Some(())
+    }
+}
+
+impl Parser<'_> {
+    fn expression(&mut self) -> Result<Term, AsmError> {
+        let mut term = self.term()?;
+        while let Some(&(_, op)) = self.chars.peek() {
+            if op != '+' && op != '-' {
+                break;
+            }
+            self.chars.next();
+            let rhs = self.term()?;
+            let rhs = if op == '+' { Some(rhs) } else { rhs.negate() };
+            rhs.and_then(|rhs| term.add(rhs))
+                .ok_or_else(|| self.overflow())?;
+        }
+        Ok(term)
+    }
+
+    fn term(&mut self) -> Result<Term, AsmError> {
+        let mut term = self.factor()?;
+        while let Some(&(_, op)) = self.chars.peek() {
+            if op != '*' && op != '/' {
+                break;
+            }
+            self.chars.next();
+            let rhs = self.factor()?;
+            if term.relative != 0 || rhs.relative != 0 {
+                return Err(AsmError::RelativeProduct(self.expr.to_string()));
+            }
+            if !term.externals.is_empty() || !rhs.externals.is_empty() {
+                return Err(AsmError::ExternalProduct(self.expr.to_string()));
+            }
+            term.value = if op == '*' {
+                term.value.checked_mul(rhs.value)
+            } else if rhs.value == 0 {
+                return Err(AsmError::DivisionByZero(self.expr.to_string()));
+            } else {
+                term.value.checked_div(rhs.value)
+            }
+            .ok_or_else(|| self.overflow())?;
+        }
+        Ok(term)
+    }
+
+    fn factor(&mut self) -> Result<Term, AsmError> {
+        match self.chars.next() {
+            Some((_, '-')) => self.factor()?.negate().ok_or_else(|| self.overflow()),
+            Some((_, '(')) => {
+                let inner = self.expression()?;
+                match self.chars.next() {
+                    Some((_, ')')) => Ok(inner),
+                    _ => Err(AsmError::MissingParen(self.expr.to_string())),
+                }
+            }
+            Some((_, '*')) => {
+                self.relative_blocks.push(self.block);
+                Ok(Term {
+                    value: self.locctr as i64,
+                    relative: 1,
+                    externals: Vec::new(),
+                })
+            }
+            Some((start, c)) if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                let quoted = matches!(c, 'X' | 'x') && self.peek_is('\'');
+                if quoted {
+                    self.chars.next();
+                    loop {
+                        match self.chars.next() {
+                            Some((i, '\'')) => {
+                                end = i + 1;
+                                break;
+                            }
+                            Some(_) => {}
+                            None => {
+                                return Err(AsmError::UnterminatedConstant(self.expr.to_string()))
+                            }
+                        }
+                    }
+                } else {
+                    while let Some(&(i, c)) = self.chars.peek() {
+                        if !(c.is_ascii_alphanumeric() || c == '_') {
+                            break;
+                        }
+                        end = i + c.len_utf8();
+                        self.chars.next();
+                    }
+                }
+                let expr = self.expr;
+                self.operand(&expr[start..end])
+            }
+            Some((_, c)) => Err(AsmError::UnexpectedCharacter(c, self.expr.to_string())),
+            None => Err(AsmError::IncompleteExpression(self.expr.to_string())),
+        }
+    }
+
+    fn operand(&mut self, token: &str) -> Result<Term, AsmError> {
+        if token.ends_with('\'') || token.starts_with(|c: char| c.is_ascii_digit()) {
+            return parse_number(&token.to_uppercase(), 10).map(|v| Term::absolute(v as i64));
+        }
+        let symbol = self
+            .symbol_table
+            .get(token)
+            .ok_or_else(|| AsmError::UndefinedSymbol(token.to_string()))?;
+        Ok(match symbol.kind {
+            SymbolKind::Absolute => Term::absolute(signed(symbol.value)),
+            SymbolKind::Relative => {
+                self.relative_blocks.push(symbol.block);
+                Term {
+                    value: symbol.value as i64,
+                    relative: 1,
+                    externals: Vec::new(),
+                }
+            }
+            SymbolKind::External => Term {
+                externals: vec![(1, token.to_string())],
+                ..Default::default()
+            },
+        })
+    }
+
+    fn overflow(&self) -> AsmError {
+        AsmError::ExpressionOverflow(self.expr.to_string())
+    }
+
+    fn peek_is(&mut self, c: char) -> bool {
+        matches!(self.chars.peek(), Some(&(_, next)) if next == c)
+    }
+}
+
+/// Absolute symbols hold a 24-bit word, so `OFF EQU -3` reads back as -3.
+fn signed(value: u32) -> i64 {
+    if value & 0x800000 != 0 {
+        value as i64 - 0x1000000
+    } else {
+        value as i64
+    }
+}
@@ -0,0 +1,185 @@
+//! Output backends for tools that want memory contents rather than the
+//! SIC object program: a flat binary image, Intel HEX and S-records.
+//!
+//! Control sections are placed one after another from the start of the
+//! main section and their M records applied, as a linking loader would.
+use crate::object::{hex, Modification, ObjectProgram};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// The most data bytes in one Intel HEX or S-record line.
+const RECORD_BYTES: usize = 16;
+
+/// The load address of each control section.
+fn load_addresses(program: &ObjectProgram) -> Vec<u32> {
+    let mut address = program.sections.first().map_or(0, |s| s.section.start);
+    program
+        .sections
+        .iter()
+        .map(|object_section| {
+            let load = address;
+            address += object_section.section.length;
+            load
+        })
+        .collect()
+}
+
+/// Every assembled byte by its address, relocated and linked. Reserved
+/// space has no entry.
+pub fn memory(program: &ObjectProgram) -> BTreeMap<u32, u8> {
+    let loads = load_addresses(program);
+    // The external symbol table: section names and EXTDEFs by their
+    // loaded address.
+    let mut symbols: HashMap<&str, u32> = HashMap::new();
+    for (object_section, &load) in program.sections.iter().zip(loads.iter()) {
+        let start = object_section.section.start;
+        symbols.entry(&object_section.section.name).or_insert(load);
+        for (name, address) in object_section.definitions.iter() {
+            symbols.entry(name).or_insert(load + address - start);
+        }
+    }
+    let mut memory = BTreeMap::new();
+    for (object_section, &load) in program.sections.iter().zip(loads.iter()) {
+        let section = &object_section.section;
+        for code in object_section.code.iter() {
+            for (i, &byte) in code.bytes.iter().enumerate() {
+                memory.insert(load + code.address - section.start + i as u32, byte);
+            }
+        }
+        for m in object_section.modifications.iter() {
+            // Fields were assembled for the section's own start address.
+            let offset = match m.symbol.as_deref().filter(|&name| name != section.name) {
+                None => Some(load.wrapping_sub(section.start)),
+                Some(name) => symbols.get(name).copied(),
+            };
+            // An undefined external reference is left for the loader to report.
+            if let Some(offset) = offset {
+                modify(&mut memory, m, load + m.address - section.start, offset);
+            }
+        }
+    }
+    memory
+}
+
+/// Adds (or with a `-` sign subtracts) `offset` to the `m.half_bytes`
+/// half-bytes at `address`, keeping the half-byte in front of an odd-length
+/// field.
+fn modify(memory: &mut BTreeMap<u32, u8>, m: &Modification, address: u32, offset: u32) {
+    let len = m.half_bytes.div_ceil(2);
+    let Some(field) = (address..address + len)
+        .map(|a| memory.get(&a).copied())
+        .collect::<Option<Vec<u8>>>()
+    else {
+        return;
+    };
+    let value = field.iter().fold(0u64, |value, &b| value << 8 | b as u64);
+    let mask = (1u64 << (4 * m.half_bytes)) - 1;
+    let adjusted = if m.sign == '-' {
+        value.wrapping_sub(offset as u64)
+    } else {
+        value.wrapping_add(offset as u64)
+    };
+    let value = value & !mask | adjusted & mask;
+    for (i, a) in (address..address + len).enumerate() {
+        memory.insert(a, (value >> (8 * (len as usize - 1 - i))) as u8);
+    }
+}
+
+/// The memory image from the start of the program to its end, with
+/// RESB/RESW space and other gaps set to `fill`.
+pub fn binary(program: &ObjectProgram, fill: u8) -> Vec<u8> {
+    let start = program.sections.first().map_or(0, |s| s.section.start);
+    let memory = memory(program);
+    let end = program
+        .sections
+        .iter()
+        .zip(load_addresses(program))
+        .map(|(object_section, load)| load + object_section.section.length)
+        .chain(memory.keys().last().map(|&address| address + 1))
+        .max()
+        .unwrap_or(start);
+    let mut image = vec![fill; end.saturating_sub(start) as usize];
+    for (address, byte) in memory {
+        if let Some(slot) = address
+            .checked_sub(start)
+            .and_then(|i| image.get_mut(i as usize))
+        {
+            *slot = byte;
+        }
+    }
+    image
+}
+
+/// Intel HEX: data records with extended linear address records above
+/// 64K, the entry point as a start linear address record, and EOF.
+pub fn intel_hex(program: &ObjectProgram) -> String {
+    let mut out = String::new();
+    let mut upper = 0;
+    for (address, bytes) in runs(&memory(program)) {
+        if address >> 16 != upper {
+            upper = address >> 16;
+            hex_record(&mut out, 0, 0x04, &(upper as u16).to_be_bytes());
+        }
+        hex_record(&mut out, address as u16, 0x00, &bytes);
+    }
+    if let Some(entry) = program.sections.first().and_then(|s| s.entry) {
+        hex_record(&mut out, 0, 0x05, &entry.to_be_bytes());
+    }
+    hex_record(&mut out, 0, 0x01, &[]);
+    out
+}
+
+fn hex_record(out: &mut String, address: u16, kind: u8, data: &[u8]) {
+    let mut record = vec![data.len() as u8];
+    record.extend_from_slice(&address.to_be_bytes());
+    record.push(kind);
+    record.extend_from_slice(data);
+    let sum = record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    record.push(sum.wrapping_neg());
+    let _ = writeln!(out, ":{}", hex(&record));
+}
+
+/// Motorola S-records: an S0 header with the program name, S2 data
+/// records (24-bit addresses) and an S8 record with the entry point.
+pub fn srecord(program: &ObjectProgram) -> String {
+    let mut out = String::new();
+    let name = program
+        .sections
+        .first()
+        .map_or("", |s| s.section.name.as_str());
+    s_record(&mut out, 0, &[0, 0], name.as_bytes());
+    for (address, bytes) in runs(&memory(program)) {
+        s_record(&mut out, 2, &address.to_be_bytes()[1..], &bytes);
+    }
+    let entry = program.sections.first().and_then(|s| s.entry).unwrap_or(0);
+    s_record(&mut out, 8, &entry.to_be_bytes()[1..], &[]);
+    out
+}
+
+fn s_record(out: &mut String, kind: u8, address: &[u8], data: &[u8]) {
+    let mut record = vec![(address.len() + data.len() + 1) as u8];
+    record.extend_from_slice(address);
+    record.extend_from_slice(data);
+    let sum = record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    record.push(!sum);
+    let _ = writeln!(out, "S{}{}", kind, hex(&record));
+}
+
+/// Contiguous runs of at most `RECORD_BYTES` bytes, never crossing a
+/// 64K boundary, as `(start address, bytes)`.
+fn runs(memory: &BTreeMap<u32, u8>) -> Vec<(u32, Vec<u8>)> {
+    let mut runs: Vec<(u32, Vec<u8>)> = Vec::new();
+    for (&address, &byte) in memory.iter() {
+        match runs.last_mut() {
+            Some((start, bytes))
+                if bytes.len() < RECORD_BYTES
+                    && *start + bytes.len() as u32 == address
+                    && address & 0xFFFF != 0 =>
+            {
+                bytes.push(byte);
+            }
+            _ => runs.push((address, vec![byte])),
+        }
+    }
+    runs
+}
@@ -0,0 +1,217 @@
+//! The `--format json` output. The schema is described in the README;
+//! `version` changes whenever a field is renamed, removed or changes
+//! meaning, while new fields may be added within a version.
+use crate::diagnostic::Diagnostics;
+use crate::line::{FormatDirective, Line};
+use crate::messages::Lang;
+use crate::object::{hex, CodeByLine, ObjectProgram, ObjectSection};
+use crate::optables::Format;
+use std::fmt::Write;
+
+/// The schema version written to the `version` field.
+pub const JSON_VERSION: u32 = 1;
+
+/// Renders the program, every assembled line and all diagnostics as JSON.
+pub fn json(
+    source: &str,
+    program: &ObjectProgram,
+    diagnostics: &Diagnostics,
+    lang: Lang,
+) -> String {
+    let source_lines: Vec<&str> = source.lines().collect();
+    let main = program.sections.first();
+    let mut out = String::new();
+    let _ = writeln!(out, "{{");
+    let _ = writeln!(out, "  \"version\": {},", JSON_VERSION);
+    let _ = writeln!(
+        out,
+        "  \"name\": {},",
+        main.map_or("null".to_string(), |s| string(&s.section.name))
+    );
+    let _ = writeln!(out, "  \"start\": {},", main.map_or(0, |s| s.section.start));
+    let _ = writeln!(
+        out,
+        "  \"length\": {},",
+        main.map_or(0, |s| s.section.length)
+    );
+    let _ = writeln!(
+        out,
+        "  \"entry\": {},",
+        main.and_then(|s| s.entry)
+            .map_or("null".to_string(), |e| e.to_string())
+    );
+    let code_by_line = program.code_by_line();
+    let sections: Vec<String> = program
+        .sections
+        .iter()
+        .map(|section| json_section(section, &code_by_line, &source_lines))
+        .collect();
+    let _ = writeln!(
+        out,
+        "  \"sections\": [{}{}],",
+        sections.join(","),
+        newline(&sections, "  ")
+    );
+    let diagnostics: Vec<String> = diagnostics
+        .sorted()
+        .into_iter()
+        .map(|d| {
+            let (id, _) = d.error.parts();
+            format!(
+                "\n    {{\"severity\": {}, \"line\": {}, \"column\": {}, \"id\": {}, \"message\": {}}}",
+                string(d.severity.message_id()),
+                d.line,
+                d.span.map_or("null".to_string(), |s| s.column.to_string()),
+                string(id),
+                string(&lang.message(&d.error))
+            )
+        })
+        .collect();
+    let _ = writeln!(
+        out,
+        "  \"diagnostics\": [{}{}]",
+        diagnostics.join(","),
+        newline(&diagnostics, "  ")
+    );
+    let _ = writeln!(out, "}}");
+    out
+}
+
+fn json_section(
+    object_section: &ObjectSection,
+    code_by_line: &CodeByLine,
+    source_lines: &[&str],
+) -> String {
+    let section = &object_section.section;
+    let symbols: Vec<String> = section
+        .symbol_table
+        .sorted()
+        .into_iter()
+        .map(|(name, symbol)| {
+            format!(
+                "\n        {{\"name\": {}, \"value\": {}, \"type\": {}, \"line\": {}}}",
+                string(name),
+                symbol.value,
+                string(symbol.kind.name()),
+                symbol.line
+            )
+        })
+        .collect();
+    let literals: Vec<String> = section
+        .literal_table
+        .iter()
+        .map(|literal| {
+            format!(
+                "\n        {{\"name\": {}, \"value\": {}, \"bytes\": {}, \"line\": {}}}",
+                string(&literal.name),
+                literal
+                    .address
+                    .map_or("null".to_string(), |a| a.to_string()),
+                string(&hex(&literal.bytes)),
+                literal.line
+            )
+        })
+        .collect();
+    let lines: Vec<String> = section
+        .lines
+        .iter()
+        .map(|line| json_line(line, source_lines, code_by_line.get(line)))
+        .collect();
+    format!(
+        "\n    {{\n      \"name\": {},\n      \"start\": {},\n      \"length\": {},\n      \"symbols\": [{}{}],\n      \"literals\": [{}{}],\n      \"lines\": [{}{}]\n    }}",
+        string(&section.name),
+        section.start,
+        section.length,
+        symbols.join(","),
+        newline(&symbols, "      "),
+        literals.join(","),
+        newline(&literals, "      "),
+        lines.join(","),
+        newline(&lines, "      "),
+    )
+}
+
+fn json_line(line: &Line, source_lines: &[&str], bytes: Option<&[u8]>) -> String {
+    let format = match line.get_fmt() {
+        FormatDirective::Format(Format::FMT1) => "1",
+        FormatDirective::Format(Format::FMT2) => "2",
+        FormatDirective::Format(Format::FMT3_4) => "3",
+        FormatDirective::Format(Format::FMT4) => "4",
+        _ => "null",
+    };
+    // n i x b p e, straight from the assembled instruction.
+    let flags = match (format, bytes) {
+        ("3" | "4", Some(bytes)) => {
+            let bits = [
+                bytes[0] & 0x02,
+                bytes[0] & 0x01,
+                bytes[1] & 0x80,
+                bytes[1] & 0x40,
+                bytes[1] & 0x20,
+                bytes[1] & 0x10,
+            ];
+            let names = ["n", "i", "x", "b", "p", "e"];
+            let fields: Vec<String> = names
+                .iter()
+                .zip(bits)
+                .map(|(name, bit)| format!("\"{}\": {}", name, bit != 0))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        }
+        _ => "null".to_string(),
+    };
+    let operands: Vec<&str> = [line.get_operand1(), line.get_operand2()]
+        .into_iter()
+        .flatten()
+        .map(|s| s.as_str())
+        .collect();
+    let source = source_lines
+        .get(line.get_line_no().wrapping_sub(1))
+        .copied()
+        .unwrap_or("");
+    format!(
+        "\n        {{\"line\": {}, \"address\": {}, \"source\": {}, \"label\": {}, \"mnemonic\": {}, \"operand\": {}, \"format\": {}, \"flags\": {}, \"object\": {}}}",
+        line.get_line_no(),
+        line.get_memory(),
+        string(source),
+        line.get_symbol().map_or("null".to_string(), |s| string(s)),
+        string(line.get_op()),
+        if operands.is_empty() {
+            "null".to_string()
+        } else {
+            string(&operands.join(","))
+        },
+        format,
+        flags,
+        bytes.map_or("null".to_string(), |b| string(&hex(b))),
+    )
+}
+
+/// Closes a non-empty array on its own line.
+fn newline(items: &[String], indent: &str) -> String {
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!("\n{}", indent)
+    }
+}
+
+/// A JSON string literal.
+fn string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
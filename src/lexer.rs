@@ -0,0 +1,184 @@
+use crate::diagnostic::Span;
+use crate::optables::{OperandUse, Optab};
+
+/// A field of a source line and the (1-based) column it starts at.
+#[derive(Debug, Clone, Copy)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub column: usize,
+}
+
+impl Token<'_> {
+    pub fn span(&self) -> Span {
+        Span {
+            column: self.column,
+            len: self.text.len(),
+        }
+    }
+}
+
+/// The label / opcode / operand / comment fields of one source line.
+/// A comment-only line has just `comment` set.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SourceLine<'a> {
+    pub label: Option<Token<'a>>,
+    pub opcode: Option<Token<'a>>,
+    pub operand: Option<Token<'a>>,
+    pub comment: Option<Token<'a>>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    /// Fields separated by any run of spaces or tabs.
+    #[default]
+    Free,
+    /// SIC fixed columns: label 1-8, opcode 10-15, operand 18-35, comment 36-66.
+    Fixed,
+}
+
+pub fn lex_line<'a>(line: &'a str, format: SourceFormat, optab: &Optab) -> SourceLine<'a> {
+    match format {
+        SourceFormat::Free => lex_free(line, optab),
+        SourceFormat::Fixed => lex_fixed(line),
+    }
+}
+
+fn lex_free<'a>(line: &'a str, optab: &Optab) -> SourceLine<'a> {
+    let mut source = SourceLine::default();
+    let words = split_words(line);
+    let Some(first) = words.first() else {
+        return source;
+    };
+    if first.text.starts_with('.') {
+        source.comment = Some(rest_of_line(line, first.column));
+        return source;
+    }
+    let mut words = words.iter().peekable();
+    // A label starts in column 1, unless the line is just an instruction
+    // written flush left (`RSUB`, `LDA BUFFER`).
+    let first_is_label = first.column == 1
+        && !(is_mnemonic(first.text, optab)
+            && words
+                .clone()
+                .nth(1)
+                .is_none_or(|w| !is_mnemonic(w.text, optab)));
+    if first_is_label {
+        source.label = words.next().copied();
+    }
+    source.opcode = words.next().copied();
+    if let Some(opcode) = source.opcode {
+        if optab.operand_use(opcode.text) != OperandUse::None {
+            if let Some(&&word) = words.peek().filter(|w| !w.text.starts_with('.')) {
+                words.next();
+                let mut end = word.column - 1 + word.text.len();
+                // `BUFFER, X` and `A ,S` still form one operand.
+                while line[..end].ends_with(',')
+                    || words.peek().is_some_and(|w| w.text.starts_with(','))
+                {
+                    match words.next() {
+                        Some(next) => end = next.column - 1 + next.text.len(),
+                        None => break,
+                    }
+                }
+                source.operand = Some(Token {
+                    text: &line[word.column - 1..end],
+                    column: word.column,
+                });
+            }
+        }
+    }
+    if let Some(word) = words.next() {
+        source.comment = Some(rest_of_line(line, word.column));
+    }
+    source
+}
+
+fn lex_fixed(line: &str) -> SourceLine<'_> {
+    let mut source = SourceLine::default();
+    if line.starts_with('.') {
+        source.comment = Some(rest_of_line(line, 1));
+        return source;
+    }
+    let field = |from: usize, to: Option<usize>| {
+        let start = from.min(line.len());
+        let end = to.map_or(line.len(), |to| to.min(line.len()));
+        let text = line.get(start..end.max(start))?;
+        let trimmed = text.trim();
+        (!trimmed.is_empty()).then(|| Token {
+            text: trimmed,
+            column: start + text.len() - text.trim_start().len() + 1,
+        })
+    };
+    source.label = field(0, Some(8));
+    source.opcode = field(9, Some(15));
+    source.operand = field(17, Some(35));
+    source.comment = field(35, None);
+    source
+}
+
+fn is_mnemonic(word: &str, optab: &Optab) -> bool {
+    optab.is_opcode(word.trim_start_matches('+')).is_some()
+}
+
+fn rest_of_line(line: &str, column: usize) -> Token<'_> {
+    Token {
+        text: line[column - 1..].trim_end(),
+        column,
+    }
+}
+
+/// Splits a line on spaces and tabs, keeping quoted text such as
+/// `C'A B,C.'` inside a single word. Everything from the first `.`
+/// outside quotes on is returned as one final comment word.
+fn split_words(line: &str) -> Vec<Token<'_>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut in_quote = false;
+    for (i, c) in line.char_indices() {
+        if c == '\'' {
+            in_quote = !in_quote;
+        }
+        // Outside a quoted constant a '.' always starts the comment,
+        // even without whitespace in front of it (`LDA BUFFER.note`).
+        if c == '.' && !in_quote {
+            if let Some(s) = start.take() {
+                words.push(Token {
+                    text: &line[s..i],
+                    column: s + 1,
+                });
+            }
+            words.push(rest_of_line(line, i + 1));
+            return words;
+        }
+        if c.is_whitespace() && !in_quote {
+            if let Some(s) = start.take() {
+                words.push(Token {
+                    text: &line[s..i],
+                    column: s + 1,
+                });
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        words.push(Token {
+            text: line[s..].trim_end(),
+            column: s + 1,
+        });
+    }
+    words
+}
+
+/// Splits `BUFFER,X` or `A,S` at the first comma outside a quoted constant.
+pub fn split_operand(operand: &str) -> (&str, &str) {
+    let mut in_quote = false;
+    for (i, c) in operand.char_indices() {
+        match c {
+            '\'' => in_quote = !in_quote,
+            ',' if !in_quote => return (operand[..i].trim_end(), operand[i + 1..].trim()),
+            _ => {}
+        }
+    }
+    (operand, "")
+}
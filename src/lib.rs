@@ -0,0 +1,36 @@
+//! A two-pass SIC/XE assembler.
+//!
+//! `assemble` turns source text into an `ObjectProgram`, whose `Display`
+//! is the H/D/R/T/M/E object program, or into the `Diagnostics` that
+//! stopped it.
+pub mod assembler;
+pub mod block;
+pub mod constant;
+pub mod diagnostic;
+pub mod error;
+pub mod expr;
+pub mod image;
+pub mod json;
+pub mod lexer;
+pub mod line;
+pub mod listing;
+pub mod literal;
+pub mod messages;
+pub mod object;
+pub mod optables;
+mod pass1;
+mod pass2;
+pub mod section;
+pub mod symtab;
+pub mod writer;
+pub mod xref;
+
+pub use assembler::{assemble, Assembler, Options};
+pub use diagnostic::{Diagnostic, Diagnostics};
+pub use error::AsmError;
+pub use line::Line;
+pub use object::ObjectProgram;
+pub use optables::{Directive, Optab};
+pub use symtab::SymbolTable;
+pub use writer::FileWriter;
+pub use xref::CrossReference;
@@ -1,4 +1,5 @@
-use crate::{optables::Format, Directive};
+use crate::diagnostic::Span;
+use crate::optables::{Directive, Format};
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum AddrMode {
@@ -7,6 +8,18 @@ pub enum AddrMode {
     Indirect = 0x04,
     Index = 0x08,
 }
+impl AddrMode {
+    /// Splits the `#` / `@` prefix off an operand.
+    pub fn from_operand(operand: &str) -> (Self, &str) {
+        if let Some(rest) = operand.strip_prefix('#') {
+            (AddrMode::Immediate, rest)
+        } else if let Some(rest) = operand.strip_prefix('@') {
+            (AddrMode::Indirect, rest)
+        } else {
+            (AddrMode::Simple, operand)
+        }
+    }
+}
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum FormatDirective {
@@ -16,7 +29,9 @@ pub enum FormatDirective {
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Line {
+    line_no: usize,
     memory: u32,
+    block: usize,
     symbol: Option<String>,
     op: String,
     operand1: Option<String>,
@@ -24,12 +39,14 @@ pub struct Line {
     code: u16,
     fmt: FormatDirective,
     address_mode: AddrMode,
+    operand_span: Option<Span>,
 }
 
 #[allow(dead_code)]
 #[allow(clippy::too_many_arguments)]
 impl Line {
     pub fn new(
+        line_no: usize,
         memory: u32,
         symbol: Option<String>,
         op: String,
@@ -40,7 +57,9 @@ impl Line {
         address_mode: AddrMode,
     ) -> Self {
         Line {
+            line_no,
             memory,
+            block: 0,
             symbol,
             op,
             operand1,
@@ -48,8 +67,18 @@ impl Line {
             code,
             fmt: fmt.clone(),
             address_mode,
+            operand_span: None,
         }
     }
+    pub fn set_memory(&mut self, memory: u32) {
+        self.memory = memory;
+    }
+    pub fn set_block(&mut self, block: usize) {
+        self.block = block;
+    }
+    pub fn set_operand_span(&mut self, span: Span) {
+        self.operand_span = Some(span);
+    }
     pub fn set_symbol(&mut self, symbol: String) {
         self.symbol = Some(symbol);
     }
@@ -74,9 +103,15 @@ impl Line {
     pub fn get_symbol(&self) -> Option<&String> {
         self.symbol.as_ref()
     }
+    pub fn get_line_no(&self) -> usize {
+        self.line_no
+    }
     pub fn get_memory(&self) -> u32 {
         self.memory
     }
+    pub fn get_block(&self) -> usize {
+        self.block
+    }
     pub fn get_op(&self) -> &String {
         &self.op
     }
@@ -95,4 +130,7 @@ impl Line {
     pub fn get_address_mode(&self) -> &AddrMode {
         &self.address_mode
     }
+    pub fn get_operand_span(&self) -> Option<Span> {
+        self.operand_span
+    }
 }
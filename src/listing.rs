@@ -0,0 +1,148 @@
+use crate::diagnostic::{Diagnostic, Diagnostics};
+use crate::lexer::{lex_line, SourceFormat, Token};
+use crate::line::Line;
+use crate::messages::Lang;
+use crate::object::{hex, ObjectProgram};
+use crate::optables::Optab;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Renders the assembly listing: every source line with its location
+/// and object code, the diagnostics under the line they belong to, and
+/// the symbol and literal tables of each control section.
+pub fn listing(
+    source: &str,
+    program: &ObjectProgram,
+    diagnostics: &Diagnostics,
+    optab: &Optab,
+    format: SourceFormat,
+    lang: Lang,
+) -> String {
+    let mut lines: HashMap<usize, Vec<&Line>> = HashMap::new();
+    for line in program.sections.iter().flat_map(|s| s.section.lines.iter()) {
+        lines.entry(line.get_line_no()).or_default().push(line);
+    }
+    let code_by_line = program.code_by_line();
+    let object = |line: &Line| code_by_line.get(line).map_or(String::new(), hex);
+    let mut by_line: HashMap<usize, Vec<&Diagnostic>> = HashMap::new();
+    for diagnostic in diagnostics.sorted() {
+        by_line.entry(diagnostic.line).or_default().push(diagnostic);
+    }
+    let no_lines = Vec::new();
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:>5}  {:<6}{:<9}{:<9}{:<19}{:<13}Comment",
+        "Line", "Loc", "Label", "Opcode", "Operand", "Object code"
+    );
+    for (index, text) in source.lines().enumerate() {
+        let line_no = index + 1;
+        let statement = lex_line(text, format, optab);
+        let assembled = lines.get(&line_no).unwrap_or(&no_lines);
+        if statement.opcode.is_none() && statement.label.is_none() {
+            // A comment line starts in the label column.
+            let comment = field_text(statement.comment);
+            row(&mut out, line_no, "", comment, "", "", "", "");
+        } else {
+            let line = assembled.iter().find(|l| l.get_op() != "*");
+            let location = line.map_or(String::new(), |l| format!("{:04X}", l.get_memory()));
+            row(
+                &mut out,
+                line_no,
+                &location,
+                field_text(statement.label),
+                field_text(statement.opcode),
+                field_text(statement.operand),
+                &line.map_or(String::new(), |l| object(l)),
+                field_text(statement.comment),
+            );
+        }
+        for diagnostic in by_line.get(&line_no).unwrap_or(&Vec::new()) {
+            annotate(&mut out, diagnostic, lang);
+        }
+        // The literal pool placed by this LTORG or END.
+        for pool in assembled.iter().filter(|l| l.get_op() == "*") {
+            let literal = pool.get_operand1().map_or("", |op| op.as_str());
+            let location = format!("{:04X}", pool.get_memory());
+            row(&mut out, 0, &location, "*", literal, "", &object(pool), "");
+        }
+    }
+    for diagnostic in by_line.get(&0).unwrap_or(&Vec::new()) {
+        annotate(&mut out, diagnostic, lang);
+    }
+
+    for object_section in program.sections.iter() {
+        let section = &object_section.section;
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Symbol table {}", section.name);
+        let _ = writeln!(out, "{:<9}{:<8}Type", "Name", "Value");
+        for (name, symbol) in section.symbol_table.sorted() {
+            let _ = writeln!(
+                out,
+                "{:<9}{:06X}  {}",
+                name,
+                symbol.value,
+                symbol.kind.name()
+            );
+        }
+        let literals: Vec<_> = section.literal_table.iter().collect();
+        if !literals.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "Literal table {}", section.name);
+            let _ = writeln!(out, "{:<13}{:<8}Length", "Literal", "Value");
+            for literal in literals {
+                let value = literal
+                    .address
+                    .map_or(String::new(), |a| format!("{:06X}", a));
+                let _ = writeln!(
+                    out,
+                    "{:<13}{:<8}{}",
+                    literal.name,
+                    value,
+                    literal.bytes.len()
+                );
+            }
+        }
+    }
+    out
+}
+
+#[allow(clippy::too_many_arguments)]
+fn row(
+    out: &mut String,
+    line_no: usize,
+    location: &str,
+    label: &str,
+    opcode: &str,
+    operand: &str,
+    object: &str,
+    comment: &str,
+) {
+    let number = if line_no == 0 {
+        String::new()
+    } else {
+        line_no.to_string()
+    };
+    let text = format!(
+        "{:>5}  {:<6}{:<9}{:<9}{:<19}{:<13}{}",
+        number, location, label, opcode, operand, object, comment
+    );
+    let _ = writeln!(out, "{}", text.trim_end());
+}
+
+fn annotate(out: &mut String, diagnostic: &Diagnostic, lang: Lang) {
+    let _ = writeln!(
+        out,
+        "       ***** {}: {}",
+        lang.text(diagnostic.severity.message_id(), &[]),
+        lang.message(&diagnostic.error)
+    );
+    if let Some(help) = lang.help(&diagnostic.error) {
+        let _ = writeln!(out, "       ***** {}: {}", lang.text("help", &[]), help);
+    }
+}
+
+fn field_text(token: Option<Token<'_>>) -> &str {
+    token.map_or("", |t| t.text)
+}
@@ -0,0 +1,87 @@
+use crate::constant::{is_byte_literal, literal_constant};
+use crate::error::AsmError;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct Literal {
+    pub name: String,
+    pub bytes: Vec<u8>,
+    pub address: Option<u32>,
+    pub block: usize,
+    /// The LTORG or END line that placed the literal.
+    pub line: usize,
+}
+
+/// LITTAB: literals in order of first appearance, each stored once.
+#[derive(Debug, Default)]
+pub struct LiteralTable {
+    literals: Vec<Literal>,
+    index: HashMap<String, usize>,
+}
+
+impl LiteralTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, name: &str) -> Result<(), AsmError> {
+        let name = normalize(name);
+        if self.index.contains_key(&name) {
+            return Ok(());
+        }
+        let bytes = literal_constant(&name)?;
+        self.index.insert(name.clone(), self.literals.len());
+        self.literals.push(Literal {
+            name,
+            bytes,
+            address: None,
+            block: 0,
+            line: 0,
+        });
+        Ok(())
+    }
+
+    /// Places every literal without an address at `locctr` onwards in `block`,
+    /// returning the literals that make up the pool.
+    pub fn dump_pending(&mut self, locctr: &mut u32, block: usize, line: usize) -> Vec<Literal> {
+        let mut pool = Vec::new();
+        for literal in self.literals.iter_mut().filter(|l| l.address.is_none()) {
+            literal.address = Some(*locctr);
+            literal.block = block;
+            literal.line = line;
+            *locctr += literal.bytes.len() as u32;
+            pool.push(literal.clone());
+        }
+        pool
+    }
+
+    pub fn relocate(&mut self, block_starts: &[u32]) {
+        for literal in self.literals.iter_mut() {
+            if let Some(address) = literal.address.as_mut() {
+                *address += block_starts[literal.block];
+            }
+        }
+    }
+
+    /// The literals in order of first appearance.
+    pub fn iter(&self) -> impl Iterator<Item = &Literal> {
+        self.literals.iter()
+    }
+
+    pub fn address(&self, name: &str) -> Option<u32> {
+        self.get(name).and_then(|literal| literal.address)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Literal> {
+        self.index.get(&normalize(name)).map(|&i| &self.literals[i])
+    }
+}
+
+/// `=c'..'` and `=x'..'` are the same literals as `=C'..'` and `=X'..'`.
+fn normalize(name: &str) -> String {
+    if is_byte_literal(name) {
+        name[..2].to_uppercase() + &name[2..]
+    } else {
+        name.to_string()
+    }
+}
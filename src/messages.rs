@@ -0,0 +1,211 @@
+use crate::error::AsmError;
+
+/// Language of the diagnostics, from `--lang` or the `LANG` variable.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    En,
+    ZhTw,
+}
+
+impl Lang {
+    /// Accepts `--lang` values and locale names: `en`, `zh-TW`, `zh_TW.UTF-8`.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.split('.').next().unwrap_or("").replace('_', "-");
+        match tag.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Lang::En),
+            t if t.starts_with("en-") => Some(Lang::En),
+            "zh-tw" | "zh-hant" | "zh-hant-tw" => Some(Lang::ZhTw),
+            _ => None,
+        }
+    }
+
+    /// The language named by `LANG`, English if unset or untranslated.
+    pub fn from_env() -> Self {
+        std::env::var("LANG")
+            .ok()
+            .and_then(|lang| Lang::from_tag(&lang))
+            .unwrap_or_default()
+    }
+
+    pub fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => EN,
+            Lang::ZhTw => ZH_TW,
+        }
+    }
+
+    /// Looks up message `id` and fills in its `{0}`, `{1}`, ... arguments.
+    /// Falls back to English for a message missing from the catalog.
+    pub fn text(self, id: &str, args: &[String]) -> String {
+        let template = lookup(self.catalog(), id)
+            .or_else(|| lookup(EN, id))
+            .unwrap_or(id);
+        let mut text = template.to_string();
+        for (i, arg) in args.iter().enumerate() {
+            text = text.replace(&format!("{{{}}}", i), arg);
+        }
+        text
+    }
+
+    pub fn message(self, error: &AsmError) -> String {
+        let (id, mut args) = error.parts();
+        // The nested error is translated along with the outer one.
+        if let AsmError::OrgForwardReference(inner) = error {
+            args = vec![self.message(inner)];
+        }
+        self.text(id, &args)
+    }
+
+    /// A hint shown below the offending line.
+    pub fn help(self, error: &AsmError) -> Option<String> {
+        match error {
+            AsmError::InvalidOpcode {
+                suggestion: Some(suggestion),
+                ..
+            } => Some(self.text("did-you-mean", std::slice::from_ref(suggestion))),
+            _ => None,
+        }
+    }
+}
+
+fn lookup(catalog: &[(&str, &'static str)], id: &str) -> Option<&'static str> {
+    catalog
+        .iter()
+        .find(|(key, _)| *key == id)
+        .map(|(_, text)| *text)
+}
+
+#[rustfmt::skip]
+const EN: &[(&str, &str)] = &[
+    ("error", "error"),
+    ("warning", "warning"),
+    ("help", "help"),
+    ("did-you-mean", "did you mean {0}?"),
+    ("summary", "{0} error(s), {1} warning(s)"),
+    ("option-needs-value", "{0} needs a value"),
+    ("option-needs-file", "{0} needs a file name"),
+    ("output-needs-file", "{0} needs a file name or -"),
+    ("unknown-language", "unknown language {0}, expected en or zh-TW"),
+    ("unknown-format", "unknown format {0}, expected obj, json, bin, hex or srec"),
+    ("invalid-fill", "--fill expects a hex byte such as FF, not {0}"),
+    ("unknown-option", "unknown option {0}"),
+    ("malformed-line", "malformed line: a label without an opcode"),
+    ("invalid-opcode", "{0} is not a valid opcode"),
+    ("extended-format", "{0} can't be used with extended format"),
+    ("missing-label", "{0} requires a label"),
+    ("invalid-index-register", "{0} is not a valid index register"),
+    ("indexed-immediate", "indexed addressing can't be combined with # or @"),
+    ("addressing-prefix", "# and @ only apply to format 3 and 4 instructions, not {0}"),
+    ("literal-not-allowed", "literal {0} is not allowed here"),
+    ("symbol-list-expected", "{0} expects a list of symbols"),
+    ("missing-operand", "{0} requires an operand"),
+    ("unterminated-constant", "unterminated constant {0}"),
+    ("empty-constant", "empty constant {0}"),
+    ("unpaired-quote", "unpaired quote in {0}"),
+    ("invalid-character", "invalid character {0} in {1}"),
+    ("invalid-hex-digit", "invalid hex digit {0} in {1}"),
+    ("odd-hex-digits", "odd number of hex digits in {0}"),
+    ("invalid-byte-constant", "invalid BYTE constant {0}"),
+    ("invalid-literal", "invalid literal {0}"),
+    ("invalid-hex-number", "invalid hexadecimal number {0}"),
+    ("invalid-decimal-number", "invalid decimal number {0}"),
+    ("number-too-large", "number {0} is too large"),
+    ("word-out-of-range", "WORD value {0} does not fit in 24 bits"),
+    ("unexpected-character", "unexpected {0} in expression {1}"),
+    ("missing-paren", "missing ')' in {0}"),
+    ("incomplete-expression", "incomplete expression {0}"),
+    ("illegal-relative", "illegal relative expression {0}"),
+    ("relative-across-blocks", "relative terms of {0} are in different program blocks"),
+    ("relative-product", "relative term can't be multiplied or divided in {0}"),
+    ("external-product", "external reference can't be multiplied or divided in {0}"),
+    ("division-by-zero", "division by zero in {0}"),
+    ("expression-overflow", "expression {0} is too large"),
+    ("undefined-symbol", "undefined symbol {0}"),
+    ("duplicate-symbol", "duplicate symbol {0}"),
+    ("equ-external", "EQU can't refer to external symbols in {0}"),
+    ("org-forward-reference", "ORG value must be defined beforehand: {0}"),
+    ("org-without-previous", "ORG without a previous ORG value"),
+    ("org-before-start", "ORG address {0} is before the start of the program"),
+    ("end-external", "END operand {0} can't be an external symbol"),
+    ("undefined-extdef", "EXTDEF symbol {0} is not defined in {1}"),
+    ("external-needs-format4", "external reference {0} requires format 4"),
+    ("displacement-out-of-range", "displacement out of range, use +format 4 or BASE"),
+    ("value-out-of-range", "value {0} out of range for format {1}"),
+    ("missing-register", "{0} requires a register operand"),
+    ("unknown-register", "unknown register {0}"),
+    ("missing-number", "{0} requires a numeric operand"),
+    ("number-out-of-range", "{0} must be a number between {1} and {2}"),
+    ("single-operand", "{0} takes a single operand"),
+    ("address-out-of-range", "address beyond the end of memory (FFFFF)"),
+    ("no-start", "no START directive found"),
+    ("no-end", "no END directive found"),
+    ("unused-symbol", "symbol {0} is defined but never referenced"),
+];
+
+#[rustfmt::skip]
+const ZH_TW: &[(&str, &str)] = &[
+    ("error", "錯誤"),
+    ("warning", "警告"),
+    ("help", "提示"),
+    ("did-you-mean", "您是不是要輸入 {0}？"),
+    ("summary", "{0} 個錯誤，{1} 個警告"),
+    ("option-needs-value", "{0} 需要一個值"),
+    ("option-needs-file", "{0} 需要檔案名稱"),
+    ("output-needs-file", "{0} 需要檔案名稱或 -"),
+    ("unknown-language", "不明的語言 {0}，應為 en 或 zh-TW"),
+    ("unknown-format", "不明的格式 {0}，應為 obj、json、bin、hex 或 srec"),
+    ("invalid-fill", "--fill 需要十六進位的位元組（例如 FF），而不是 {0}"),
+    ("unknown-option", "不明的選項 {0}"),
+    ("malformed-line", "格式不正確：只有標籤而沒有指令"),
+    ("invalid-opcode", "{0} 不是有效的指令"),
+    ("extended-format", "{0} 不能使用延伸格式"),
+    ("missing-label", "{0} 需要標籤"),
+    ("invalid-index-register", "{0} 不是有效的索引暫存器"),
+    ("indexed-immediate", "索引定址不能與 # 或 @ 同時使用"),
+    ("addressing-prefix", "# 與 @ 只能用在格式 3 和 4 的指令，不能用在 {0}"),
+    ("literal-not-allowed", "此處不能使用常值 {0}"),
+    ("symbol-list-expected", "{0} 需要以逗號分隔的符號"),
+    ("missing-operand", "{0} 需要運算元"),
+    ("unterminated-constant", "常數 {0} 缺少結尾的引號"),
+    ("empty-constant", "常數 {0} 是空的"),
+    ("unpaired-quote", "{0} 中有未成對的引號"),
+    ("invalid-character", "{1} 中有無效的字元 {0}"),
+    ("invalid-hex-digit", "{1} 中有無效的十六進位數字 {0}"),
+    ("odd-hex-digits", "{0} 的十六進位位數是奇數"),
+    ("invalid-byte-constant", "無效的 BYTE 常數 {0}"),
+    ("invalid-literal", "無效的常值 {0}"),
+    ("invalid-hex-number", "無效的十六進位數字 {0}"),
+    ("invalid-decimal-number", "無效的十進位數字 {0}"),
+    ("number-too-large", "數字 {0} 太大"),
+    ("word-out-of-range", "WORD 的值 {0} 超出 24 位元"),
+    ("unexpected-character", "運算式 {1} 中有非預期的 {0}"),
+    ("missing-paren", "{0} 缺少 ')'"),
+    ("incomplete-expression", "運算式 {0} 不完整"),
+    ("illegal-relative", "不合法的相對運算式 {0}"),
+    ("relative-across-blocks", "{0} 的相對項目位於不同的程式區塊"),
+    ("relative-product", "{0} 中的相對項不能做乘除"),
+    ("external-product", "{0} 中的外部參考不能做乘除"),
+    ("division-by-zero", "{0} 中除以零"),
+    ("expression-overflow", "運算式 {0} 的值太大"),
+    ("undefined-symbol", "未定義的符號 {0}"),
+    ("duplicate-symbol", "重複定義的符號 {0}"),
+    ("equ-external", "EQU 不能參考外部符號：{0}"),
+    ("org-forward-reference", "ORG 的值必須事先定義：{0}"),
+    ("org-without-previous", "ORG 之前沒有可還原的位址"),
+    ("org-before-start", "ORG 的位址 {0} 在程式起始位址之前"),
+    ("end-external", "END 的運算元 {0} 不能是外部符號"),
+    ("undefined-extdef", "EXTDEF 的符號 {0} 未在 {1} 中定義"),
+    ("external-needs-format4", "外部參考 {0} 必須使用格式 4"),
+    ("displacement-out-of-range", "位移超出範圍，請改用 +格式 4 或 BASE"),
+    ("value-out-of-range", "值 {0} 超出格式 {1} 的範圍"),
+    ("missing-register", "{0} 需要暫存器運算元"),
+    ("unknown-register", "未知的暫存器 {0}"),
+    ("missing-number", "{0} 需要數字運算元"),
+    ("number-out-of-range", "{0} 必須是 {1} 到 {2} 之間的數字"),
+    ("single-operand", "{0} 只能有一個運算元"),
+    ("address-out-of-range", "位址超出記憶體範圍（FFFFF）"),
+    ("no-start", "找不到 START 指引"),
+    ("no-end", "找不到 END 指引"),
+    ("unused-symbol", "符號 {0} 已定義但從未被參考"),
+];
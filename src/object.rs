@@ -0,0 +1,158 @@
+use crate::diagnostic::Diagnostics;
+use crate::line::Line;
+use crate::optables::Directive;
+use crate::section::Section;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The object code assembled from one source line.
+#[derive(Debug, Clone)]
+pub struct ObjectCode {
+    pub line_no: usize,
+    pub address: u32,
+    pub bytes: Vec<u8>,
+}
+
+/// Bytes as upper-case hex digits, the way every output format writes them.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// The object code of a program looked up by the line it came from.
+pub struct CodeByLine<'a>(HashMap<(usize, u32), &'a [u8]>);
+
+impl<'a> CodeByLine<'a> {
+    pub fn get(&self, line: &Line) -> Option<&'a [u8]> {
+        match Directive::from_code(line.get_code()) {
+            // LTORG and END share their address with the pool they place.
+            Some(Directive::LTORG | Directive::END) => None,
+            _ => self
+                .0
+                .get(&(line.get_line_no(), line.get_memory()))
+                .copied(),
+        }
+    }
+}
+
+/// An M record: `half_bytes` half-bytes at `address` are adjusted by the
+/// loader, either by the program's load address or by `sign` `symbol`.
+#[derive(Debug, Clone)]
+pub struct Modification {
+    pub address: u32,
+    pub half_bytes: u32,
+    pub sign: char,
+    pub symbol: Option<String>,
+}
+
+/// One control section after pass 2: the pass 1 `Section` with its
+/// object code, external definitions and modifications.
+#[derive(Debug)]
+pub struct ObjectSection {
+    pub section: Section,
+    pub definitions: Vec<(String, u32)>,
+    pub code: Vec<ObjectCode>,
+    pub modifications: Vec<Modification>,
+    /// The transfer address, set on the main section only.
+    pub entry: Option<u32>,
+}
+
+/// An assembled program. Its `Display` is the H/D/R/T/M/E text format.
+#[derive(Debug)]
+pub struct ObjectProgram {
+    pub sections: Vec<ObjectSection>,
+    /// Warnings from a successful assembly.
+    pub warnings: Diagnostics,
+}
+
+/// The most object code bytes a T record holds.
+const TEXT_RECORD_BYTES: usize = 30;
+
+impl ObjectSection {
+    /// Groups the object code into T records: contiguous runs of at most
+    /// 30 bytes, as `(start address, bytes)`. A code too long for one
+    /// record, such as a long BYTE constant, continues in the next.
+    pub fn text_records(&self) -> Vec<(u32, Vec<u8>)> {
+        let mut records: Vec<(u32, Vec<u8>)> = Vec::new();
+        for code in self.code.iter() {
+            let mut address = code.address;
+            let mut rest = code.bytes.as_slice();
+            while !rest.is_empty() {
+                match records.last_mut() {
+                    Some((start, bytes))
+                        if bytes.len() + rest.len() <= TEXT_RECORD_BYTES
+                            && *start + bytes.len() as u32 == address =>
+                    {
+                        bytes.extend_from_slice(rest);
+                        rest = &[];
+                    }
+                    _ => {
+                        let (head, tail) = rest.split_at(rest.len().min(TEXT_RECORD_BYTES));
+                        records.push((address, head.to_vec()));
+                        address += head.len() as u32;
+                        rest = tail;
+                    }
+                }
+            }
+        }
+        records
+    }
+}
+
+impl fmt::Display for ObjectSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let section = &self.section;
+        writeln!(
+            f,
+            "H{:<6}{:06X}{:06X}",
+            section.name, section.start, section.length
+        )?;
+        for definitions in self.definitions.chunks(6) {
+            write!(f, "D")?;
+            for (name, address) in definitions {
+                write!(f, "{:<6}{:06X}", name, address)?;
+            }
+            writeln!(f)?;
+        }
+        for names in section.extref.chunks(6) {
+            let record: String = names.iter().map(|name| format!("{:<6}", name)).collect();
+            writeln!(f, "R{}", record.trim_end())?;
+        }
+        for (start, bytes) in self.text_records() {
+            write!(f, "T{:06X}{:02X}", start, bytes.len())?;
+            for byte in bytes {
+                write!(f, "{:02X}", byte)?;
+            }
+            writeln!(f)?;
+        }
+        for m in self.modifications.iter() {
+            write!(f, "M{:06X}{:02X}", m.address, m.half_bytes)?;
+            if let Some(symbol) = &m.symbol {
+                write!(f, "{}{}", m.sign, symbol)?;
+            }
+            writeln!(f)?;
+        }
+        match self.entry {
+            Some(address) => writeln!(f, "E{:06X}", address),
+            None => writeln!(f, "E"),
+        }
+    }
+}
+
+impl ObjectProgram {
+    pub fn code_by_line(&self) -> CodeByLine<'_> {
+        let code = self.sections.iter().flat_map(|s| s.code.iter());
+        CodeByLine(
+            code.map(|code| ((code.line_no, code.address), code.bytes.as_slice()))
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for ObjectProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for section in self.sections.iter() {
+            write!(f, "{}", section)?;
+        }
+        Ok(())
+    }
+}
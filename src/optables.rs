@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::ops::BitOr;
 
-use crate::FormatDirective;
+use crate::line::FormatDirective;
 impl BitOr for Format {
     type Output = u8;
 
@@ -30,6 +30,95 @@ pub enum Directive {
     NOBASE = 0x106,
     START = 0x107,
     END = 0x108,
+    LTORG = 0x109,
+    EQU = 0x10A,
+    ORG = 0x10B,
+    USE = 0x10C,
+    CSECT = 0x10D,
+    EXTDEF = 0x10E,
+    EXTREF = 0x10F,
+}
+
+impl Directive {
+    pub fn from_code(code: u16) -> Option<Self> {
+        match code {
+            0x101 => Some(Directive::BYTE),
+            0x102 => Some(Directive::WORD),
+            0x103 => Some(Directive::RESB),
+            0x104 => Some(Directive::RESW),
+            0x105 => Some(Directive::BASE),
+            0x106 => Some(Directive::NOBASE),
+            0x107 => Some(Directive::START),
+            0x108 => Some(Directive::END),
+            0x109 => Some(Directive::LTORG),
+            0x10A => Some(Directive::EQU),
+            0x10B => Some(Directive::ORG),
+            0x10C => Some(Directive::USE),
+            0x10D => Some(Directive::CSECT),
+            0x10E => Some(Directive::EXTDEF),
+            0x10F => Some(Directive::EXTREF),
+            _ => None,
+        }
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy)]
+pub enum Register {
+    A = 0,
+    X = 1,
+    L = 2,
+    B = 3,
+    S = 4,
+    T = 5,
+    F = 6,
+    PC = 8,
+    SW = 9,
+}
+
+impl Register {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "A" => Some(Register::A),
+            "X" => Some(Register::X),
+            "L" => Some(Register::L),
+            "B" => Some(Register::B),
+            "S" => Some(Register::S),
+            "T" => Some(Register::T),
+            "F" => Some(Register::F),
+            "PC" => Some(Register::PC),
+            "SW" => Some(Register::SW),
+            _ => None,
+        }
+    }
+}
+
+/// Operand shape of a format 2 instruction.
+#[derive(Debug, Clone, Copy)]
+pub enum Format2Operands {
+    Registers,     /* r1,r2 */
+    Register,      /* r1 */
+    RegisterCount, /* r1,n */
+    Number,        /* n */
+}
+
+impl Format2Operands {
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            0xB4 | 0xB8 => Format2Operands::Register,
+            0xA4 | 0xA8 => Format2Operands::RegisterCount,
+            0xB0 => Format2Operands::Number,
+            _ => Format2Operands::Registers,
+        }
+    }
+}
+
+/// Whether a mnemonic is followed by an operand field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandUse {
+    None,
+    Optional,
+    Required,
 }
 
 #[derive(Debug)]
@@ -42,6 +131,12 @@ pub struct Optab {
     instructions: HashMap<&'static str, Instruction>,
 }
 
+impl Default for Optab {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Optab {
     pub fn new() -> Self {
         let mut instructions = HashMap::new();
@@ -108,6 +203,13 @@ impl Optab {
                 code: 0xA0,
             },
         );
+        instructions.insert(
+            "CSECT",
+            Instruction {
+                fmt: FormatDirective::Format(Format::FMT0),
+                code: Directive::CSECT as u16,
+            },
+        );
         instructions.insert(
             "DIV",
             Instruction {
@@ -137,6 +239,27 @@ impl Optab {
                 code: Directive::END as u16,
             },
         );
+        instructions.insert(
+            "EQU",
+            Instruction {
+                fmt: FormatDirective::Format(Format::FMT0),
+                code: Directive::EQU as u16,
+            },
+        );
+        instructions.insert(
+            "EXTDEF",
+            Instruction {
+                fmt: FormatDirective::Format(Format::FMT0),
+                code: Directive::EXTDEF as u16,
+            },
+        );
+        instructions.insert(
+            "EXTREF",
+            Instruction {
+                fmt: FormatDirective::Format(Format::FMT0),
+                code: Directive::EXTREF as u16,
+            },
+        );
         instructions.insert(
             "FIX",
             Instruction {
@@ -256,6 +379,13 @@ impl Optab {
                 code: 0xD0,
             },
         );
+        instructions.insert(
+            "LTORG",
+            Instruction {
+                fmt: FormatDirective::Format(Format::FMT0),
+                code: Directive::LTORG as u16,
+            },
+        );
         instructions.insert(
             "MUL",
             Instruction {
@@ -298,6 +428,13 @@ impl Optab {
                 code: 0x44,
             },
         );
+        instructions.insert(
+            "ORG",
+            Instruction {
+                fmt: FormatDirective::Format(Format::FMT0),
+                code: Directive::ORG as u16,
+            },
+        );
         instructions.insert(
             "RD",
             Instruction {
@@ -494,6 +631,13 @@ impl Optab {
                 code: 0xB8,
             },
         );
+        instructions.insert(
+            "USE",
+            Instruction {
+                fmt: FormatDirective::Format(Format::FMT0),
+                code: Directive::USE as u16,
+            },
+        );
         instructions.insert(
             "WD",
             Instruction {
@@ -515,4 +659,45 @@ impl Optab {
     pub fn is_opcode(&self, op: &str) -> Option<&Instruction> {
         self.instructions.get(op.to_uppercase().as_str())
     }
+
+    /// The mnemonic closest to a misspelled `op`, at most two edits
+    /// away, for "did you mean" hints.
+    pub fn suggest(&self, op: &str) -> Option<&'static str> {
+        let op = op.trim_start_matches('+').to_uppercase();
+        self.instructions
+            .keys()
+            .map(|&name| (edit_distance(&op, name), name))
+            .filter(|&(distance, _)| distance <= 2 && distance < op.len())
+            .min()
+            .map(|(_, name)| name)
+    }
+
+    /// Unknown mnemonics are assumed to take an operand.
+    pub fn operand_use(&self, op: &str) -> OperandUse {
+        let Some(instruction) = self.is_opcode(op.trim_start_matches('+')) else {
+            return OperandUse::Required;
+        };
+        match (&instruction.fmt, Directive::from_code(instruction.code)) {
+            (FormatDirective::Format(Format::FMT1), _) => OperandUse::None,
+            (_, Some(Directive::NOBASE | Directive::LTORG | Directive::CSECT)) => OperandUse::None,
+            (_, Some(Directive::ORG | Directive::USE | Directive::END)) => OperandUse::Optional,
+            _ if instruction.code == 0x4C => OperandUse::None, /* RSUB */
+            _ => OperandUse::Required,
+        }
+    }
+}
+
+/// Levenshtein distance between two mnemonics.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
@@ -0,0 +1,382 @@
+use crate::constant::{byte_constant, parse_number};
+use crate::diagnostic::{Diagnostic, Diagnostics, Span};
+use crate::error::AsmError;
+use crate::expr::evaluate;
+use crate::lexer::{lex_line, split_operand};
+use crate::line::{AddrMode, FormatDirective, Line};
+use crate::optables::{Directive, Format, Optab};
+use crate::section::Section;
+use crate::symtab::SymbolKind;
+use crate::Options;
+
+/// One past the last address of the 1 MB SIC/XE memory.
+const MEMORY_SIZE: u32 = 0x100000;
+
+/// Pass 1: assigns addresses, builds SYMTAB and LITTAB and splits the
+/// program into control sections.
+pub struct Pass1<'a> {
+    options: &'a Options,
+    optab: &'a Optab,
+    sections: Vec<Section>,
+    section: Section,
+    locctr: u32,
+    /// Block and LOCCTR to return to at the next operandless `ORG`.
+    org_saved: Option<(usize, u32)>,
+}
+
+impl<'a> Pass1<'a> {
+    pub fn new(options: &'a Options, optab: &'a Optab) -> Self {
+        Pass1 {
+            options,
+            optab,
+            sections: Vec::new(),
+            section: Section::new(""),
+            locctr: 0,
+            org_saved: None,
+        }
+    }
+
+    /// Processes one source line. Problems that still leave the line
+    /// usable go to `diagnostics`; the one returned as `Err` means the
+    /// line was skipped.
+    pub fn line(
+        &mut self,
+        line_no: usize,
+        text: &str,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<(), Diagnostic> {
+        let source = lex_line(text, self.options.source_format, self.optab);
+        let Some(opcode_token) = source.opcode else {
+            if let Some(label) = source.label {
+                return Err(Diagnostic::error(line_no, AsmError::MalformedLine).at(label.span()));
+            }
+            return Ok(());
+        };
+        let operand_field = source.operand.map_or("", |t| t.text);
+        let (operand1, operand2) = split_operand(operand_field);
+        let tokens = [
+            source.label.map_or("", |t| t.text),
+            opcode_token.text,
+            operand1,
+            operand2,
+        ];
+        let label_span = source
+            .label
+            .map_or(Span { column: 1, len: 1 }, |t| t.span());
+        let opcode_span = opcode_token.span();
+        // Without an operand, point just past the opcode.
+        let operand_span = source.operand.map_or(
+            Span {
+                column: opcode_token.column + opcode_token.text.len() + 1,
+                len: 1,
+            },
+            |t| t.span(),
+        );
+        let label_error = |e: AsmError| Diagnostic::error(line_no, e).at(label_span);
+        let opcode_error = |e: AsmError| Diagnostic::error(line_no, e).at(opcode_span);
+        let operand_error = |e: AsmError| Diagnostic::error(line_no, e).at(operand_span);
+        let opcode = tokens[1].trim_start_matches('+');
+        let use_extended_format = tokens[1].starts_with('+');
+        let Some(obj) = self.optab.is_opcode(opcode) else {
+            // Still define the label so later references to it
+            // don't turn into a cascade of undefined symbols.
+            if !tokens[0].is_empty() {
+                if let Err(e) = self.section.symbol_table.define(
+                    tokens[0],
+                    self.locctr,
+                    SymbolKind::Relative,
+                    self.section.block_table.current(),
+                    line_no,
+                ) {
+                    diagnostics.push(label_error(e));
+                }
+            }
+            return Err(opcode_error(AsmError::InvalidOpcode {
+                opcode: tokens[1].to_string(),
+                suggestion: self.optab.suggest(opcode).map(str::to_string),
+            }));
+        };
+        let code = obj.code;
+        let mut formatter = obj.fmt.clone();
+        if use_extended_format {
+            if let FormatDirective::Format(Format::FMT3_4) = obj.fmt {
+                formatter = FormatDirective::Format(Format::FMT4);
+            } else {
+                diagnostics.push(opcode_error(AsmError::ExtendedFormat(opcode.to_string())));
+            }
+        }
+        if code == Directive::START as u16 {
+            self.section.name = tokens[0].to_string();
+            self.section.start = if self.options.relocatable {
+                0
+            } else {
+                let start = parse_number(tokens[2], 16).map_err(operand_error)?;
+                if start >= MEMORY_SIZE {
+                    return Err(operand_error(AsmError::AddressOutOfRange));
+                }
+                start
+            };
+        }
+        if code == Directive::CSECT as u16 {
+            if tokens[0].is_empty() {
+                return Err(opcode_error(AsmError::MissingLabel(tokens[1].to_string())));
+            }
+            dump_literals(&mut self.section, &mut self.locctr, line_no);
+            self.finish_section(line_no, diagnostics);
+            self.sections.push(std::mem::replace(
+                &mut self.section,
+                Section::new(tokens[0]),
+            ));
+            self.locctr = 0;
+            self.org_saved = None;
+        }
+        if code == Directive::USE as u16 {
+            self.section.block_table.switch(tokens[2], &mut self.locctr);
+        }
+        let (mut address_mode, operand1) = AddrMode::from_operand(tokens[2]);
+        // Only format 3 and 4 have n and i bits for # and @ to set.
+        if !matches!(address_mode, AddrMode::Simple)
+            && !matches!(
+                formatter,
+                FormatDirective::Format(Format::FMT3_4 | Format::FMT4)
+            )
+        {
+            diagnostics.push(operand_error(AsmError::AddressingPrefix(
+                opcode.to_string(),
+            )));
+            address_mode = AddrMode::Simple;
+        }
+        let mut operand2 = tokens.get(3).copied().filter(|s| !s.is_empty());
+        if let (FormatDirective::Format(Format::FMT3_4 | Format::FMT4), Some(index_register)) =
+            (&formatter, operand2)
+        {
+            if !index_register.eq_ignore_ascii_case("X") {
+                return Err(operand_error(AsmError::InvalidIndexRegister(
+                    index_register.to_string(),
+                )));
+            }
+            if !matches!(address_mode, AddrMode::Simple) {
+                return Err(operand_error(AsmError::IndexedImmediate));
+            }
+            address_mode = AddrMode::Index;
+            operand2 = None;
+        }
+        // Format 2 checks its own operand shapes and EXTDEF/EXTREF take
+        // the whole field as a list; nothing else has a second operand.
+        let list = code == Directive::EXTDEF as u16 || code == Directive::EXTREF as u16;
+        if operand2.is_some()
+            && !list
+            && !matches!(formatter, FormatDirective::Format(Format::FMT2))
+        {
+            diagnostics.push(operand_error(AsmError::SingleOperand(opcode.to_string())));
+            operand2 = None;
+        }
+        if operand1.starts_with('=') {
+            if !matches!(
+                formatter,
+                FormatDirective::Format(Format::FMT3_4 | Format::FMT4)
+            ) || matches!(address_mode, AddrMode::Immediate | AddrMode::Indirect)
+            {
+                return Err(operand_error(AsmError::LiteralNotAllowed(
+                    operand1.to_string(),
+                )));
+            }
+            self.section
+                .literal_table
+                .add(operand1)
+                .map_err(operand_error)?;
+        }
+        let locctr = self.locctr;
+        let mut line = Line::new(
+            line_no,
+            locctr,
+            Some(tokens[0].to_string()).filter(|s| !s.is_empty()),
+            tokens[1].to_string(),
+            Some(operand1.to_string()).filter(|s| !s.is_empty()),
+            operand2.map(|s| s.to_string()),
+            code,
+            formatter.clone(),
+            address_mode,
+        );
+        line.set_block(self.section.block_table.current());
+        line.set_operand_span(operand_span);
+        let symbol_table = &mut self.section.symbol_table;
+        if code == Directive::EQU as u16 {
+            let symbol = line
+                .get_symbol()
+                .ok_or_else(|| opcode_error(AsmError::MissingLabel(tokens[1].to_string())))?;
+            let operand = line.get_operand1().map_or("", |op| op.as_str());
+            let value = evaluate(
+                operand,
+                symbol_table,
+                locctr,
+                self.section.block_table.current(),
+            )
+            .map_err(operand_error)?;
+            if value.spans_blocks {
+                return Err(operand_error(AsmError::RelativeAcrossBlocks(
+                    operand.to_string(),
+                )));
+            }
+            if !value.externals.is_empty() {
+                return Err(operand_error(AsmError::EquExternal(operand.to_string())));
+            }
+            if let Err(e) = symbol_table.define(
+                symbol,
+                value.value as u32 & 0xFFFFFF,
+                value.kind,
+                value.block,
+                line_no,
+            ) {
+                diagnostics.push(label_error(e));
+            }
+        } else if let Some(symbol) = line.get_symbol() {
+            if let Err(e) = symbol_table.define(
+                symbol,
+                locctr,
+                SymbolKind::Relative,
+                self.section.block_table.current(),
+                line_no,
+            ) {
+                diagnostics.push(label_error(e));
+            }
+        }
+        if code == Directive::EXTDEF as u16 || code == Directive::EXTREF as u16 {
+            let mut column = operand_span.column;
+            for item in operand_field.split(',') {
+                let name = item.trim();
+                let name_span = Span {
+                    column: column + item.len() - item.trim_start().len(),
+                    len: name.len(),
+                };
+                column += item.len() + 1;
+                if name.is_empty() {
+                    return Err(operand_error(AsmError::SymbolListExpected(
+                        tokens[1].to_string(),
+                    )));
+                }
+                if code == Directive::EXTREF as u16 {
+                    if let Err(e) =
+                        self.section
+                            .symbol_table
+                            .define(name, 0, SymbolKind::External, 0, line_no)
+                    {
+                        diagnostics.push(Diagnostic::error(line_no, e).at(name_span));
+                    }
+                    self.section.extref.push(name.to_string());
+                } else {
+                    self.section
+                        .extdef
+                        .push((name.to_string(), line_no, name_span));
+                }
+            }
+        }
+        if code == Directive::ORG as u16 {
+            match line.get_operand1() {
+                Some(operand) => {
+                    let value = evaluate(
+                        operand,
+                        &self.section.symbol_table,
+                        locctr,
+                        self.section.block_table.current(),
+                    )
+                    .map_err(|e| operand_error(AsmError::OrgForwardReference(Box::new(e))))?;
+                    if value.spans_blocks {
+                        return Err(operand_error(AsmError::RelativeAcrossBlocks(
+                            operand.to_string(),
+                        )));
+                    }
+                    // A relative value lies in its symbol's block; an
+                    // absolute address is counted from START in the
+                    // default block.
+                    let (block, target) = match value.kind {
+                        SymbolKind::Relative => (value.block, value.value),
+                        _ => (0, value.value - self.section.start as i64),
+                    };
+                    if target < 0 {
+                        return Err(operand_error(AsmError::OrgBeforeStart(operand.to_string())));
+                    }
+                    if self.section.start as i64 + target >= MEMORY_SIZE as i64 {
+                        return Err(operand_error(AsmError::AddressOutOfRange));
+                    }
+                    let block_table = &mut self.section.block_table;
+                    self.org_saved
+                        .get_or_insert((block_table.current(), locctr));
+                    block_table.enter(block, &mut self.locctr);
+                    self.locctr = target as u32;
+                }
+                None => {
+                    let (block, saved) = self
+                        .org_saved
+                        .take()
+                        .ok_or_else(|| opcode_error(AsmError::OrgWithoutPrevious))?;
+                    self.section.block_table.enter(block, &mut self.locctr);
+                    self.locctr = saved;
+                }
+            }
+        }
+        let reserved = match Directive::from_code(code) {
+            Some(Directive::WORD) => Some(3),
+            Some(Directive::RESW) => parse_number(tokens[2], 10)
+                .map_err(operand_error)?
+                .checked_mul(3),
+            Some(Directive::RESB) => Some(parse_number(tokens[2], 10).map_err(operand_error)?),
+            Some(Directive::BYTE) => {
+                Some(byte_constant(tokens[2]).map_err(operand_error)?.len() as u32)
+            }
+            _ => Some(0),
+        };
+        let size = match formatter {
+            FormatDirective::Format(Format::FMT1) => 1,
+            FormatDirective::Format(Format::FMT2) => 2,
+            FormatDirective::Format(Format::FMT3_4) => 3,
+            FormatDirective::Format(Format::FMT4) => 4,
+            _ => 0,
+        };
+        // Addresses have to stay inside memory, whatever the operand says.
+        self.locctr = reserved
+            .and_then(|reserved| self.locctr.checked_add(reserved + size))
+            .filter(|&end| self.section.start as u64 + end as u64 <= MEMORY_SIZE as u64)
+            .ok_or_else(|| operand_error(AsmError::AddressOutOfRange))?;
+        self.section.lines.push(line);
+        if code == Directive::LTORG as u16 || code == Directive::END as u16 {
+            dump_literals(&mut self.section, &mut self.locctr, line_no);
+        }
+        Ok(())
+    }
+
+    /// Ends pass 1, returning the control sections in source order.
+    pub fn finish(mut self, diagnostics: &mut Diagnostics) -> Vec<Section> {
+        self.finish_section(0, diagnostics);
+        self.sections.push(self.section);
+        self.sections
+    }
+
+    /// Lays out the current section, whose blocks together may still
+    /// not fit in memory.
+    fn finish_section(&mut self, line_no: usize, diagnostics: &mut Diagnostics) {
+        self.section.finish(self.locctr);
+        if self.section.start as u64 + self.section.length as u64 > MEMORY_SIZE as u64 {
+            diagnostics.error(line_no, AsmError::AddressOutOfRange);
+        }
+    }
+}
+
+fn dump_literals(section: &mut Section, locctr: &mut u32, line_no: usize) {
+    let block = section.block_table.current();
+    for literal in section.literal_table.dump_pending(locctr, block, line_no) {
+        let mut pool_line = Line::new(
+            line_no,
+            literal.address.unwrap_or(0),
+            None,
+            "*".to_string(),
+            Some(literal.name),
+            None,
+            Directive::BYTE as u16,
+            FormatDirective::Format(Format::FMT0),
+            AddrMode::Simple,
+        );
+        pool_line.set_block(literal.block);
+        section.lines.push(pool_line);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::constant::{byte_constant, literal_constant, parse_number, word_value};
+use crate::diagnostic::{Diagnostic, Diagnostics};
+use crate::error::AsmError;
+use crate::expr::{evaluate, Value};
+use crate::line::{AddrMode, FormatDirective, Line};
+use crate::object::{Modification, ObjectCode, ObjectProgram, ObjectSection};
+use crate::optables::{Directive, Format, Format2Operands, Register};
+use crate::section::Section;
+use crate::symtab::SymbolKind;
+use crate::Options;
+
+/// Pass 2: assembles the object code of every control section.
+/// Lines that can't be assembled are reported and left out.
+pub fn pass2(
+    sections: Vec<Section>,
+    options: &Options,
+    diagnostics: &mut Diagnostics,
+) -> ObjectProgram {
+    let has_start = sections[0]
+        .lines
+        .iter()
+        .any(|line| line.get_code() == Directive::START as u16);
+    if !has_start {
+        diagnostics.error(0, AsmError::NoStart);
+    }
+    let has_end = sections
+        .iter()
+        .flat_map(|section| section.lines.iter())
+        .any(|line| line.get_code() == Directive::END as u16);
+    if !has_end {
+        diagnostics.warning(0, AsmError::NoEnd);
+    }
+    let transfer = transfer_address(&sections).unwrap_or_else(|diagnostic| {
+        diagnostics.push(diagnostic);
+        sections[0].start
+    });
+    let sections = sections
+        .into_iter()
+        .enumerate()
+        .map(|(index, section)| {
+            let entry = if index == 0 { Some(transfer) } else { None };
+            assemble_section(section, entry, options, diagnostics)
+        })
+        .collect();
+    ObjectProgram {
+        sections,
+        warnings: Diagnostics::new(),
+    }
+}
+
+/// The first instruction to execute: the END operand, resolved in the
+/// main control section, or the start of the program without one.
+fn transfer_address(sections: &[Section]) -> Result<u32, Diagnostic> {
+    let main_section = &sections[0];
+    let end_line = sections
+        .iter()
+        .flat_map(|section| section.lines.iter())
+        .find(|line| line.get_code() == Directive::END as u16);
+    let Some((line, operand)) = end_line.and_then(|l| l.get_operand1().map(|op| (l, op))) else {
+        return Ok(main_section.start);
+    };
+    let operand_error = |e: AsmError| line_error(line, e);
+    let value = evaluate(
+        operand,
+        &main_section.symbol_table,
+        line.get_memory(),
+        line.get_block(),
+    )
+    .map_err(operand_error)?;
+    if !value.externals.is_empty() {
+        return Err(operand_error(AsmError::EndExternal(operand.to_string())));
+    }
+    Ok(value.value as u32)
+}
+
+fn assemble_section(
+    section: Section,
+    entry: Option<u32>,
+    options: &Options,
+    diagnostics: &mut Diagnostics,
+) -> ObjectSection {
+    let mut definitions = Vec::new();
+    for (name, line_no, span) in section.extdef.iter() {
+        match section.symbol_table.get(name) {
+            Some(symbol) if symbol.kind != SymbolKind::External => {
+                definitions.push((name.clone(), symbol.value));
+            }
+            _ => diagnostics.push(
+                Diagnostic::error(
+                    *line_no,
+                    AsmError::UndefinedExtdef {
+                        name: name.clone(),
+                        section: section.name.clone(),
+                    },
+                )
+                .at(*span),
+            ),
+        }
+    }
+    let relocation = |address: u32, half_bytes: u32| Modification {
+        address,
+        half_bytes,
+        sign: '+',
+        symbol: options.symbolic_modification.then(|| section.name.clone()),
+    };
+    let mut code = Vec::new();
+    let mut base: Option<u32> = None;
+    let mut modifications = Vec::new();
+    for line in section.lines.iter() {
+        match object_code(line, &section, &mut base, &mut modifications, &relocation) {
+            Ok(Some(bytes)) => code.push(ObjectCode {
+                line_no: line.get_line_no(),
+                address: line.get_memory(),
+                bytes,
+            }),
+            Ok(None) => {}
+            Err(e) => diagnostics.push(line_error(line, e)),
+        }
+    }
+    ObjectSection {
+        section,
+        definitions,
+        code,
+        modifications,
+        entry,
+    }
+}
+
+/// Assembles one line, or `None` for a line without object code.
+/// `BASE` and `NOBASE` only update `base`.
+fn object_code(
+    line: &Line,
+    section: &Section,
+    base: &mut Option<u32>,
+    modifications: &mut Vec<Modification>,
+    relocation: &impl Fn(u32, u32) -> Modification,
+) -> Result<Option<Vec<u8>>, AsmError> {
+    let symbol_table = &section.symbol_table;
+    let external = |address: u32, half_bytes: u32, (sign, name): &(char, String)| Modification {
+        address,
+        half_bytes,
+        sign: *sign,
+        symbol: Some(name.clone()),
+    };
+    if let FormatDirective::Format(Format::FMT0) = line.get_fmt() {
+        let operand = line.get_operand1().map_or("", |op| op.as_str());
+        return match Directive::from_code(line.get_code()) {
+            Some(Directive::BYTE) if operand.starts_with('=') => {
+                literal_constant(operand).map(Some)
+            }
+            Some(Directive::BYTE) => byte_constant(operand).map(Some),
+            Some(Directive::WORD) => {
+                let value = evaluate(operand, symbol_table, line.get_memory(), line.get_block())?;
+                if value.kind == SymbolKind::Relative {
+                    modifications.push(relocation(line.get_memory(), 6));
+                }
+                for reference in value.externals.iter() {
+                    modifications.push(external(line.get_memory(), 6, reference));
+                }
+                let word = word_value(value.value)?;
+                Ok(Some(word.to_be_bytes()[1..].to_vec()))
+            }
+            Some(Directive::BASE) => {
+                if operand.is_empty() {
+                    return Err(AsmError::MissingOperand(line.get_op().to_string()));
+                }
+                let value = evaluate(operand, symbol_table, line.get_memory(), line.get_block())?;
+                *base = Some(value.value as u32);
+                Ok(None)
+            }
+            Some(Directive::NOBASE) => {
+                *base = None;
+                Ok(None)
+            }
+            _ => Ok(None),
+        };
+    }
+
+    let ni = match line.get_address_mode() {
+        AddrMode::Simple => 0x3,
+        AddrMode::Immediate => 0x1,
+        AddrMode::Indirect => 0x2,
+        AddrMode::Index => 0x3,
+    };
+    let x_bit = if matches!(line.get_address_mode(), AddrMode::Index) {
+        0x8
+    } else {
+        0x0
+    };
+
+    let bytes = match line.get_fmt() {
+        FormatDirective::Format(Format::FMT1) => vec![line.get_code() as u8],
+        FormatDirective::Format(Format::FMT2) => {
+            let (r1, r2) = format2_operands(line)?;
+            vec![line.get_code() as u8, r1 << 4 | r2]
+        }
+        FormatDirective::Format(Format::FMT3_4 | Format::FMT4) => {
+            let extended = matches!(line.get_fmt(), FormatDirective::Format(Format::FMT4));
+            let pc = line.get_memory() + if extended { 4 } else { 3 };
+            let (displacement, bp) = match line.get_operand1() {
+                None => (0, 0x0),
+                Some(operand) => {
+                    let target = match section.literal_table.address(operand) {
+                        Some(address) => Value {
+                            value: address as i64,
+                            kind: SymbolKind::Relative,
+                            block: line.get_block(),
+                            spans_blocks: false,
+                            externals: Vec::new(),
+                        },
+                        None => {
+                            evaluate(operand, symbol_table, line.get_memory(), line.get_block())?
+                        }
+                    };
+                    if let (false, Some((_, name))) = (extended, target.externals.first()) {
+                        return Err(AsmError::ExternalNeedsFormat4(name.clone()));
+                    }
+                    for reference in target.externals.iter() {
+                        modifications.push(external(line.get_memory() + 1, 5, reference));
+                    }
+                    match target.kind {
+                        SymbolKind::Relative if extended => {
+                            modifications.push(relocation(line.get_memory() + 1, 5));
+                            (target.value as u32, 0x0)
+                        }
+                        SymbolKind::Relative => {
+                            relative_displacement(target.value as u32, pc, *base)
+                                .ok_or(AsmError::DisplacementOutOfRange)?
+                        }
+                        SymbolKind::Absolute | SymbolKind::External => {
+                            let limit = if extended { 0xFFFFF } else { 0xFFF };
+                            if !(0..=limit).contains(&target.value) {
+                                return Err(AsmError::ValueOutOfRange {
+                                    value: target.value,
+                                    format: if extended { 4 } else { 3 },
+                                });
+                            }
+                            (target.value as u32, 0x0)
+                        }
+                    }
+                }
+            };
+            let mut xbpe = if extended { 0x1 } else { bp };
+            xbpe |= x_bit;
+            let opcode = line.get_code() as u32 | ni;
+            if extended {
+                let word = opcode << 24 | xbpe << 20 | displacement & 0xFFFFF;
+                word.to_be_bytes().to_vec()
+            } else {
+                let word = opcode << 16 | xbpe << 12 | displacement;
+                word.to_be_bytes()[1..].to_vec()
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(bytes))
+}
+
+fn format2_operands(line: &Line) -> Result<(u8, u8), AsmError> {
+    let register = |operand: Option<&String>| {
+        let name = operand.ok_or_else(|| AsmError::MissingRegister(line.get_op().to_string()))?;
+        Register::from_name(name)
+            .map(|r| r as u8)
+            .ok_or_else(|| AsmError::UnknownRegister(name.to_string()))
+    };
+    let number = |operand: Option<&String>, range: std::ops::RangeInclusive<u8>| {
+        let text = operand.ok_or_else(|| AsmError::MissingNumber(line.get_op().to_string()))?;
+        parse_number(text, 10)
+            .ok()
+            .and_then(|n| u8::try_from(n).ok())
+            .filter(|n| range.contains(n))
+            .ok_or_else(|| AsmError::NumberOutOfRange {
+                text: text.to_string(),
+                min: *range.start(),
+                max: *range.end(),
+            })
+    };
+    let shape = Format2Operands::from_code(line.get_code());
+    if line.get_operand2().is_some()
+        && matches!(shape, Format2Operands::Register | Format2Operands::Number)
+    {
+        return Err(AsmError::SingleOperand(line.get_op().to_string()));
+    }
+    match shape {
+        Format2Operands::Registers => Ok((
+            register(line.get_operand1())?,
+            register(line.get_operand2())?,
+        )),
+        Format2Operands::Register => Ok((register(line.get_operand1())?, 0)),
+        Format2Operands::RegisterCount => Ok((
+            register(line.get_operand1())?,
+            number(line.get_operand2(), 1..=16)? - 1,
+        )),
+        Format2Operands::Number => Ok((number(line.get_operand1(), 0..=15)?, 0)),
+    }
+}
+
+/// Pass 2 problems point at the operand, where nearly all of them are.
+fn line_error(line: &Line, error: AsmError) -> Diagnostic {
+    let diagnostic = Diagnostic::error(line.get_line_no(), error);
+    match line.get_operand_span() {
+        Some(span) => diagnostic.at(span),
+        None => diagnostic,
+    }
+}
+
+/// Tries PC-relative addressing first, then base-relative.
+/// Returns the 12-bit displacement together with the b/p bits.
+fn relative_displacement(target: u32, pc: u32, base: Option<u32>) -> Option<(u32, u32)> {
+    let disp = target as i64 - pc as i64;
+    if (-2048..=2047).contains(&disp) {
+        return Some((disp as u32 & 0xFFF, 0x2));
+    }
+    match base {
+        Some(base) if (0..=4095).contains(&(target as i64 - base as i64)) => {
+            Some((target - base, 0x4))
+        }
+        _ => None,
+    }
+}
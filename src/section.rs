@@ -0,0 +1,49 @@
+use crate::block::BlockTable;
+use crate::diagnostic::Span;
+use crate::line::Line;
+use crate::literal::LiteralTable;
+use crate::symtab::SymbolTable;
+
+/// A control section: the program itself or one started by `CSECT`.
+/// Each section has its own symbols, literals, blocks and LOCCTR.
+#[derive(Debug)]
+pub struct Section {
+    pub name: String,
+    pub start: u32,
+    pub length: u32,
+    pub lines: Vec<Line>,
+    pub symbol_table: SymbolTable,
+    pub literal_table: LiteralTable,
+    pub block_table: BlockTable,
+    /// EXTDEF names with the line and columns that listed them.
+    pub extdef: Vec<(String, usize, Span)>,
+    pub extref: Vec<String>,
+}
+
+impl Section {
+    pub fn new(name: &str) -> Self {
+        Section {
+            name: name.to_string(),
+            start: 0,
+            length: 0,
+            lines: Vec::new(),
+            symbol_table: SymbolTable::new(),
+            literal_table: LiteralTable::new(),
+            block_table: BlockTable::new(),
+            extdef: Vec::new(),
+            extref: Vec::new(),
+        }
+    }
+
+    /// Ends pass 1 for the section: lays out its program blocks and
+    /// turns block offsets into addresses.
+    pub fn finish(&mut self, locctr: u32) {
+        self.length = self.block_table.finish(locctr, self.start);
+        let block_starts = self.block_table.starts();
+        for line in self.lines.iter_mut() {
+            line.set_memory(line.get_memory() + block_starts[line.get_block()]);
+        }
+        self.symbol_table.relocate(&block_starts);
+        self.literal_table.relocate(&block_starts);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::error::AsmError;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Absolute,
+    Relative,
+    External,
+}
+
+impl SymbolKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SymbolKind::Absolute => "absolute",
+            SymbolKind::Relative => "relative",
+            SymbolKind::External => "external",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub value: u32,
+    pub kind: SymbolKind,
+    pub block: usize,
+    /// The source line that defined it.
+    pub line: usize,
+}
+
+/// SYMTAB: every label and EQU name with its value and relocation type.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    symbols: HashMap<String, Symbol>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn define(
+        &mut self,
+        name: &str,
+        value: u32,
+        kind: SymbolKind,
+        block: usize,
+        line: usize,
+    ) -> Result<(), AsmError> {
+        if self.symbols.contains_key(name) {
+            return Err(AsmError::DuplicateSymbol(name.to_string()));
+        }
+        self.symbols.insert(
+            name.to_string(),
+            Symbol {
+                value,
+                kind,
+                block,
+                line,
+            },
+        );
+        Ok(())
+    }
+
+    /// Turns block offsets of relative symbols into addresses once the
+    /// block starting addresses are known.
+    pub fn relocate(&mut self, block_starts: &[u32]) {
+        for symbol in self.symbols.values_mut() {
+            if symbol.kind == SymbolKind::Relative {
+                symbol.value += block_starts[symbol.block];
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.get(name)
+    }
+
+    /// Every symbol, sorted by name.
+    pub fn sorted(&self) -> Vec<(&str, &Symbol)> {
+        let mut symbols: Vec<(&str, &Symbol)> = self
+            .symbols
+            .iter()
+            .map(|(name, symbol)| (name.as_str(), symbol))
+            .collect();
+        symbols.sort_by_key(|&(name, _)| name);
+        symbols
+    }
+}
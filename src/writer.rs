@@ -0,0 +1,57 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Buffered output to a file, stdout or memory (`FileWriter::new(Vec::new())`).
+pub struct FileWriter<W: Write> {
+    writer: BufWriter<W>,
+}
+
+impl<W: Write> FileWriter<W> {
+    pub fn new(writer: W) -> Self {
+        FileWriter {
+            writer: BufWriter::new(writer),
+        }
+    }
+
+    pub fn write(&mut self, content: impl AsRef<[u8]>) -> io::Result<()> {
+        self.writer.write_all(content.as_ref())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// The underlying writer, once everything is flushed into it.
+    pub fn into_inner(self) -> io::Result<W> {
+        self.writer.into_inner().map_err(|e| e.into_error())
+    }
+}
+
+impl FileWriter<File> {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(FileWriter::new(File::create(path)?))
+    }
+}
+
+/// Writes `content` to a temporary file next to `path` and renames it
+/// into place, so `path` is either left alone or completely written.
+pub fn write_atomically(path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    let temp = temp_path(path);
+    let result = FileWriter::create(&temp)
+        .and_then(|mut writer| {
+            writer.write(content)?;
+            writer.into_inner()?.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// `dir/.name.<pid>.tmp`: same directory, so the rename stays atomic,
+/// and one per process, so parallel builds don't share it.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
@@ -0,0 +1,216 @@
+use crate::diagnostic::{Diagnostic, Span};
+use crate::error::AsmError;
+use crate::expr::symbols;
+use crate::line::{FormatDirective, Line};
+use crate::object::ObjectProgram;
+use crate::optables::{Directive, Format};
+use crate::section::Section;
+use crate::symtab::SymbolKind;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// One symbol of the cross reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub value: u32,
+    pub kind: SymbolKind,
+    /// The line that defined the symbol.
+    pub defined: usize,
+    /// Every line that refers to it, in order.
+    pub references: Vec<usize>,
+}
+
+/// Symbols of one control section, sorted by name, then its literals
+/// in order of first appearance.
+#[derive(Debug)]
+pub struct SectionReference {
+    pub name: String,
+    pub entries: Vec<Entry>,
+    /// LITTAB, each literal defined by the LTORG or END that placed it.
+    pub literals: Vec<Entry>,
+    extdef: Vec<String>,
+}
+
+/// The cross reference of a program. Its `Display` is the `--xref` report.
+#[derive(Debug)]
+pub struct CrossReference {
+    pub sections: Vec<SectionReference>,
+}
+
+impl CrossReference {
+    pub fn new(program: &ObjectProgram) -> Self {
+        let mut sections: Vec<_> = program
+            .sections
+            .iter()
+            .map(|object_section| {
+                let section = &object_section.section;
+                let entries = section
+                    .symbol_table
+                    .sorted()
+                    .into_iter()
+                    .map(|(name, symbol)| {
+                        (
+                            name,
+                            Entry {
+                                name: name.to_string(),
+                                value: symbol.value,
+                                kind: symbol.kind,
+                                defined: symbol.line,
+                                references: Vec::new(),
+                            },
+                        )
+                    })
+                    .collect::<BTreeMap<_, _>>();
+                (section, entries)
+            })
+            .collect();
+        for index in 0..sections.len() {
+            for line in sections[index].0.lines.iter() {
+                // The END operand is resolved in the main section.
+                let owner = if line.get_code() == Directive::END as u16 {
+                    0
+                } else {
+                    index
+                };
+                for name in referenced_names(line) {
+                    if let Some(entry) = sections[owner].1.get_mut(name) {
+                        if entry.references.last() != Some(&line.get_line_no()) {
+                            entry.references.push(line.get_line_no());
+                        }
+                    }
+                }
+            }
+        }
+        CrossReference {
+            sections: sections
+                .into_iter()
+                .map(|(section, entries)| SectionReference {
+                    name: section.name.clone(),
+                    entries: entries.into_values().collect(),
+                    literals: literals(section),
+                    extdef: section
+                        .extdef
+                        .iter()
+                        .map(|(name, ..)| name.clone())
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Warnings for symbols nothing refers to. Section names and EXTDEF
+    /// symbols are left out: they are used from outside the section.
+    pub fn unused(&self) -> Vec<Diagnostic> {
+        let mut warnings = Vec::new();
+        for section in self.sections.iter() {
+            for entry in section.entries.iter() {
+                if !entry.references.is_empty()
+                    || entry.name == section.name
+                    || section.extdef.contains(&entry.name)
+                {
+                    continue;
+                }
+                let warning =
+                    Diagnostic::warning(entry.defined, AsmError::UnusedSymbol(entry.name.clone()));
+                // Labels start the line; EXTREF names sit in the operand.
+                warnings.push(match entry.kind {
+                    SymbolKind::External => warning,
+                    _ => warning.at(Span {
+                        column: 1,
+                        len: entry.name.len(),
+                    }),
+                });
+            }
+        }
+        warnings
+    }
+}
+
+fn literals(section: &Section) -> Vec<Entry> {
+    section
+        .literal_table
+        .iter()
+        .filter_map(|literal| {
+            let references = section
+                .lines
+                .iter()
+                // Pool lines carry the literal they place.
+                .filter(|line| line.get_op() != "*")
+                .filter(|line| {
+                    line.get_operand1()
+                        .and_then(|operand| section.literal_table.get(operand))
+                        .is_some_and(|l| l.name == literal.name)
+                })
+                .map(|line| line.get_line_no())
+                .collect();
+            Some(Entry {
+                name: literal.name.clone(),
+                value: literal.address?,
+                kind: SymbolKind::Relative,
+                defined: literal.line,
+                references,
+            })
+        })
+        .collect()
+}
+
+/// Names in the operands of `line` that may be symbols. Literals, BYTE
+/// constants, registers and the names of sections, blocks and external
+/// symbols are not references.
+fn referenced_names(line: &Line) -> Vec<&str> {
+    if let FormatDirective::Format(Format::FMT2) = line.get_fmt() {
+        return Vec::new();
+    }
+    if matches!(
+        Directive::from_code(line.get_code()),
+        Some(
+            Directive::BYTE
+                | Directive::START
+                | Directive::USE
+                | Directive::CSECT
+                | Directive::EXTDEF
+                | Directive::EXTREF
+        )
+    ) {
+        return Vec::new();
+    }
+    [line.get_operand1(), line.get_operand2()]
+        .into_iter()
+        .flatten()
+        .filter(|operand| !operand.starts_with('='))
+        .flat_map(|operand| symbols(operand))
+        .collect()
+}
+
+impl fmt::Display for CrossReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, section) in self.sections.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "Cross reference {}", section.name)?;
+            writeln!(
+                f,
+                "{:<9}{:<8}{:<10}{:>7}  References",
+                "Name", "Value", "Type", "Line"
+            )?;
+            let symbols = section.entries.iter().map(|e| (e, e.kind.name()));
+            let literals = section.literals.iter().map(|e| (e, "literal"));
+            for (entry, kind) in symbols.chain(literals) {
+                let references: Vec<String> =
+                    entry.references.iter().map(|l| l.to_string()).collect();
+                let text = format!(
+                    "{:<9}{:06X}  {:<10}{:>7}  {}",
+                    entry.name,
+                    entry.value,
+                    kind,
+                    entry.defined,
+                    references.join(" ")
+                );
+                writeln!(f, "{}", text.trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
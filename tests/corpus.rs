@@ -0,0 +1,162 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Assembles `tests/corpus/<name>.asm` and returns the object program
+/// the binary streamed to stdout.
+fn assemble(name: &str, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_sic"))
+        .args(args)
+        .args(["-o", "-"])
+        .arg(corpus().join(format!("{}.asm", name)))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}.asm failed: {}",
+        name,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn corpus() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+fn expected(name: &str) -> String {
+    fs::read_to_string(corpus().join(format!("{}.obj", name))).unwrap()
+}
+
+#[test]
+fn dots_inside_character_constants() {
+    assert_eq!(assemble("dots", &[]), expected("dots"));
+}
+
+#[test]
+fn commas_inside_character_constants() {
+    assert_eq!(assemble("commas", &[]), expected("commas"));
+}
+
+#[test]
+fn tabs_between_fields_and_inside_constants() {
+    assert_eq!(assemble("tabs", &[]), expected("tabs"));
+}
+
+#[test]
+fn doubled_quotes_inside_constants() {
+    assert_eq!(assemble("quotes", &[]), expected("quotes"));
+}
+
+#[test]
+fn fixed_columns_comment_field() {
+    assert_eq!(assemble("fixed", &["--fixed"]), expected("fixed"));
+}
+
+#[test]
+fn writes_next_to_the_input_or_to_dash_o() {
+    let dir = std::env::temp_dir().join(format!("sic-output-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy(corpus().join("dots.asm"), dir.join("dots.asm")).unwrap();
+    let run = |args: &[&str]| {
+        let status = Command::new(env!("CARGO_BIN_EXE_sic"))
+            .args(args)
+            .arg("dots.asm")
+            .current_dir(&dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    run(&[]);
+    let default = fs::read_to_string(dir.join("dots.obj")).unwrap();
+    fs::create_dir(dir.join("out")).unwrap();
+    run(&["-o", "out/dots.txt"]);
+    let named = fs::read_to_string(dir.join("out/dots.txt")).unwrap();
+    let leftovers = fs::read_dir(dir.join("out")).unwrap().count();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(default, expected("dots"));
+    assert_eq!(named, expected("dots"));
+    assert_eq!(leftovers, 1);
+}
+
+#[test]
+fn org_past_the_end_counts_toward_the_length() {
+    assert_eq!(assemble("org_length", &[]), expected("org_length"));
+}
+
+#[test]
+fn org_to_an_absolute_address() {
+    assert_eq!(assemble("org_absolute", &[]), expected("org_absolute"));
+}
+
+#[test]
+fn org_into_another_block() {
+    assert_eq!(assemble("org_block", &[]), expected("org_block"));
+}
+
+#[test]
+fn long_constants_continue_in_the_next_text_record() {
+    assert_eq!(assemble("long_byte", &[]), expected("long_byte"));
+}
+
+/// Three blocks, each with its own LOCCTR, laid out in order of first
+/// use. The T records follow the source, so they jump between blocks,
+/// and LTORG places the literals in the block in use.
+#[test]
+fn program_blocks() {
+    assert_eq!(assemble("blocks", &[]), expected("blocks"));
+}
+
+#[test]
+fn immediate_and_indirect_addressing() {
+    assert_eq!(assemble("addressing", &[]), expected("addressing"));
+}
+
+/// Format 4 with simple, indexed and immediate operands; relocatable
+/// addresses get an M record, `#1048575` does not.
+#[test]
+fn extended_format() {
+    assert_eq!(assemble("extended", &[]), expected("extended"));
+}
+
+/// Every register, `SHIFTL r,n` encoding n-1 and the ends of the SVC range.
+#[test]
+fn format_2_registers() {
+    assert_eq!(assemble("registers", &[]), expected("registers"));
+}
+
+#[test]
+fn indexed_addressing() {
+    assert_eq!(assemble("indexed", &[]), expected("indexed"));
+}
+
+/// Two control sections referring to each other: D and R records, zero
+/// fields for external references and M records naming the symbols.
+#[test]
+fn control_sections() {
+    assert_eq!(assemble("sections", &[]), expected("sections"));
+}
+
+/// M records for format 4 and relocatable WORDs only, also assembled
+/// from 0 with the section named in each M record.
+#[test]
+fn modification_records() {
+    assert_eq!(assemble("relocation", &[]), expected("relocation"));
+    assert_eq!(
+        assemble("relocation", &["--relocatable", "--symbolic-m"]),
+        expected("relocation_start0")
+    );
+}
+
+#[test]
+fn end_operand_is_the_transfer_address() {
+    assert_eq!(assemble("entry", &[]), expected("entry"));
+}
+
+/// Pairs within one block are absolute as soon as both are defined;
+/// pairs across blocks only once the blocks are laid out, in pass 2.
+#[test]
+fn relative_pairs_in_program_blocks() {
+    assert_eq!(assemble("block_pairs", &[]), expected("block_pairs"));
+}
@@ -0,0 +1,14 @@
+ADDR	START	1000
+FIRST	LDA	#3
+	+LDB	#TABLE
+	BASE	TABLE
+	LDT	#4095
+	+LDS	#4096
+	J	@RETADR
+	LDA	TABLE
+	STA	RETADR
+RETADR	RESW	1
+	RESB	2048
+TABLE	WORD	7
+	LDX	@TABLE
+	END	FIRST
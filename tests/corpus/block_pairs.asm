@@ -0,0 +1,12 @@
+PAIRS	START	0
+FIRST	LDA	#LEN
+	LDT	#BUF-FIRST
+	USE	DATA
+BUF	RESB	16
+BUFEND	EQU	*
+LEN	EQU	BUFEND-BUF
+	USE
+	RSUB
+	WORD	BUF-FIRST
+	WORD	BUFEND-BUF
+	END	FIRST
@@ -0,0 +1,20 @@
+BLOCKS	START	0
+FIRST	STL	RETADR
+	LDA	=C'EOF'
+	USE	CDATA
+RETADR	RESW	1
+LENGTH	WORD	3
+	USE	CBLKS
+BUFFER	RESB	16
+BUFEND	EQU	*
+	USE
+	LDA	LENGTH
+	LDX	#MAXLEN
+	COMP	=X'05'
+	J	@RETADR
+	USE	CDATA
+	LTORG
+MAXLEN	EQU	BUFEND-BUFFER
+	USE
+	STA	BUFFER
+	END	FIRST
@@ -0,0 +1,8 @@
+COMMAS	START	100
+FIRST	STCH	BUF,X	.indexed, with a comma in the comment
+	COMPR	A, S
+LIST	BYTE	C'A,B'
+ONE	BYTE	C','
+	LDCH	BUF , X
+BUF	RESB	3
+	END	FIRST
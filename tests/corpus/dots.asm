@@ -0,0 +1,7 @@
+DOTS	START	0
+FIRST	LDA	PI	.load the constant
+	LDA	DOTTED.no space before this comment
+PI	BYTE	C'3.14'	.four bytes
+DOTTED	BYTE	C'A.B'
+ELLIP	BYTE	C'...'	. trailing dots ...
+	END	FIRST
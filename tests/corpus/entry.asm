@@ -0,0 +1,5 @@
+ENTRY	START	200
+DATA	WORD	5
+MAIN	LDA	DATA
+	RSUB
+	END	MAIN+3
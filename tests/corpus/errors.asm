@@ -0,0 +1,9 @@
+ERRS	START	1000
+FIRST	LDA	NOPE
+	LDAX	FIRST
+FIRST	STA	BUF
+	+ADDR	A,X
+BUF	BYTE	X'ABC'
+	LDB	#BUF
+	J	MISSING
+	END	FIRST
@@ -0,0 +1,10 @@
+EXT	START	0
+FIRST	+LDA	BUFFER
+	+STA	BUFFER,X
+	+JSUB	FAR
+	+LDT	#1048575
+	+LDB	#BUFFER
+	RSUB
+BUFFER	RESB	4096
+FAR	RSUB
+	END	FIRST
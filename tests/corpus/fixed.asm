@@ -0,0 +1,4 @@
+FIXED    START   0
+FIRST    LDA     STR               load. the string
+STR      BYTE    C'A. B'           comment, with commas
+         END     FIRST
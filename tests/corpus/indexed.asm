@@ -0,0 +1,11 @@
+INDEX	START	0
+FIRST	LDX	#0
+LOOP	LDCH	TEXT,X
+	STCH	COPY,X
+	+STCH	COPY,X
+	TIX	#3
+	JLT	LOOP
+	RSUB
+TEXT	BYTE	C'ABC'
+COPY	RESB	3
+	END	FIRST
@@ -0,0 +1,5 @@
+LONG	START	0
+	LDA	#1
+TEXT	BYTE	C'THIS CONSTANT IS LONGER THAN ONE TEXT RECORD'
+	RSUB
+	END
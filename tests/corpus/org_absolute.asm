@@ -0,0 +1,6 @@
+ORGABS	START	1000
+	LDA	#0
+	ORG	X'1010'
+A	WORD	2
+	ORG
+	END
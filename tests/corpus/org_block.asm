@@ -0,0 +1,11 @@
+ORGBLK	START	0
+	LDA	A
+	USE	DATA
+T	RESB	6
+	USE
+C	WORD	7
+	ORG	T
+A	WORD	1
+	ORG
+D	WORD	8
+	END
@@ -0,0 +1,5 @@
+ORGLEN	START	0
+	ORG	100
+	WORD	1
+	ORG
+	END
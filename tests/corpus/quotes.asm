@@ -0,0 +1,5 @@
+QUOTES	START	0
+IT	BYTE	C'IT''S'	.doubled quote
+Q	BYTE	C''''
+SP	BYTE	C' '
+	END	IT
@@ -0,0 +1,13 @@
+REGS	START	0
+	CLEAR	X
+	COMPR	A,S
+	ADDR	B,T
+	RMO	F,L
+	TIXR	T
+	SHIFTL	A,1
+	SHIFTR	T,16
+	SVC	0
+	SVC	15
+	CLEAR	pc
+	CLEAR	sw
+	END
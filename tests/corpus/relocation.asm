@@ -0,0 +1,8 @@
+RELOC	START	1000
+FIRST	+JSUB	SUB
+	+LDA	#4096
+	LDA	PTR
+PTR	WORD	SUB
+DIFF	WORD	SUB-FIRST
+SUB	RSUB
+	END	FIRST
@@ -0,0 +1,18 @@
+MAIN	START	0
+	EXTDEF	BUF,BUFEND
+	EXTREF	RDREC,LENGTH
+FIRST	+JSUB	RDREC
+	+LDA	LENGTH
+	LDT	#BUFEND-BUF
+	RSUB
+BUF	RESB	8
+BUFEND	EQU	*
+RDREC	CSECT
+	EXTDEF	LENGTH
+	EXTREF	BUF,BUFEND
+	+STCH	BUF,X
+	LDA	LENGTH
+	RSUB
+LENGTH	WORD	BUFEND-BUF
+MAXLEN	WORD	BUFEND-BUF+1
+	END	FIRST
@@ -0,0 +1,4 @@
+TABS		START		0
+FIRST			LDA		TAB	.tabs	everywhere
+TAB	BYTE	C'A	B'
+	END			FIRST
@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn reports_every_error_and_fails() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let dir = std::env::temp_dir().join(format!("sic-diagnostics-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // Assemble a copy so the file name in the report is just `errors.asm`.
+    fs::copy(corpus.join("errors.asm"), dir.join("errors.asm")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_sic"))
+        .arg("errors.asm")
+        .env_remove("LANG")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let wrote_object = dir.join("errors.obj").exists();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(!output.status.success());
+    assert!(!wrote_object);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        fs::read_to_string(corpus.join("errors.stderr")).unwrap()
+    );
+}
+
+#[test]
+fn reports_in_traditional_chinese() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let dir = std::env::temp_dir().join(format!("sic-diagnostics-zh-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_sic"))
+        .args(["--lang", "zh-TW"])
+        .arg(corpus.join("errors.asm"))
+        .env("LANG", "en_US.UTF-8")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("錯誤: 未定義的符號 NOPE"));
+    assert!(stderr.contains("= 提示: 您是不是要輸入 LDA？"));
+    assert!(stderr.ends_with("6 個錯誤，0 個警告\n"));
+}
+
+#[test]
+fn rejects_bad_command_lines_with_status_2() {
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_sic"))
+            .args(args)
+            .env_remove("LANG")
+            .output()
+            .unwrap();
+        (
+            output.status.code(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    };
+    assert_eq!(
+        run(&["--bogus", "x.asm"]),
+        (Some(2), "error: unknown option --bogus\n".into())
+    );
+    assert_eq!(
+        run(&["--lang"]),
+        (Some(2), "error: --lang needs a value\n".into())
+    );
+    assert_eq!(
+        run(&["--format", "elf", "--lang", "zh-TW"]),
+        (
+            Some(2),
+            "錯誤: 不明的格式 elf，應為 obj、json、bin、hex 或 srec\n".into()
+        )
+    );
+}
@@ -0,0 +1,96 @@
+use sic::expr::{evaluate, symbols};
+use sic::symtab::{SymbolKind, SymbolTable};
+use sic::AsmError;
+
+/// BUFFER and BUFEND are relative, MAXLEN and OFF absolute, RDREC external.
+fn symbol_table() -> SymbolTable {
+    let mut symbol_table = SymbolTable::new();
+    symbol_table
+        .define("BUFFER", 0x36, SymbolKind::Relative, 0, 1)
+        .unwrap();
+    symbol_table
+        .define("BUFEND", 0x1036, SymbolKind::Relative, 0, 2)
+        .unwrap();
+    symbol_table
+        .define("MAXLEN", 4096, SymbolKind::Absolute, 0, 3)
+        .unwrap();
+    symbol_table
+        .define("RDREC", 0, SymbolKind::External, 0, 4)
+        .unwrap();
+    // OFF EQU -3, stored as a 24-bit word.
+    symbol_table
+        .define("OFF", 0xFFFFFD, SymbolKind::Absolute, 0, 5)
+        .unwrap();
+    symbol_table
+}
+
+fn value(expr: &str) -> Result<(i64, SymbolKind), AsmError> {
+    evaluate(expr, &symbol_table(), 0x100, 0).map(|v| (v.value, v.kind))
+}
+
+#[test]
+fn follows_precedence_and_parentheses() {
+    assert_eq!(value("2+3*4"), Ok((14, SymbolKind::Absolute)));
+    assert_eq!(value("(2+3)*4"), Ok((20, SymbolKind::Absolute)));
+    assert_eq!(value("20-4-6"), Ok((10, SymbolKind::Absolute)));
+    assert_eq!(value("100/7/2"), Ok((7, SymbolKind::Absolute)));
+    assert_eq!(value("-3+X'10'"), Ok((13, SymbolKind::Absolute)));
+    assert_eq!(value("MAXLEN/2"), Ok((2048, SymbolKind::Absolute)));
+}
+
+#[test]
+fn reads_negative_absolute_symbols() {
+    assert_eq!(value("OFF"), Ok((-3, SymbolKind::Absolute)));
+    assert_eq!(value("OFF+5"), Ok((2, SymbolKind::Absolute)));
+    assert_eq!(value("OFF*-2"), Ok((6, SymbolKind::Absolute)));
+    assert_eq!(value("BUFFER+OFF"), Ok((0x33, SymbolKind::Relative)));
+}
+
+#[test]
+fn pairs_relative_terms() {
+    assert_eq!(value("BUFEND-BUFFER"), Ok((0x1000, SymbolKind::Absolute)));
+    assert_eq!(value("BUFFER+3"), Ok((0x39, SymbolKind::Relative)));
+    assert_eq!(value("*"), Ok((0x100, SymbolKind::Relative)));
+    assert_eq!(value("*-BUFFER"), Ok((0xCA, SymbolKind::Absolute)));
+    assert_eq!(
+        value("BUFEND+BUFFER"),
+        Err(AsmError::IllegalRelative("BUFEND+BUFFER".into()))
+    );
+    assert_eq!(
+        value("2*BUFFER"),
+        Err(AsmError::RelativeProduct("2*BUFFER".into()))
+    );
+}
+
+#[test]
+fn keeps_external_references_for_the_loader() {
+    let value = evaluate("RDREC-3", &symbol_table(), 0, 0).unwrap();
+    assert_eq!(value.value, -3);
+    assert_eq!(value.externals, [('+', "RDREC".to_string())]);
+    assert_eq!(
+        evaluate("RDREC*2", &symbol_table(), 0, 0).unwrap_err(),
+        AsmError::ExternalProduct("RDREC*2".into())
+    );
+}
+
+#[test]
+fn reports_bad_expressions() {
+    assert_eq!(value("4/0"), Err(AsmError::DivisionByZero("4/0".into())));
+    assert_eq!(value("NOPE"), Err(AsmError::UndefinedSymbol("NOPE".into())));
+    assert_eq!(value("(1+2"), Err(AsmError::MissingParen("(1+2".into())));
+    assert_eq!(
+        value("1+"),
+        Err(AsmError::IncompleteExpression("1+".into()))
+    );
+    assert_eq!(
+        value("99999999*99999999*99999"),
+        Err(AsmError::ExpressionOverflow(
+            "99999999*99999999*99999".into()
+        ))
+    );
+}
+
+#[test]
+fn lists_referenced_symbols() {
+    assert_eq!(symbols("BUFEND-BUFFER+X'1F'*2"), ["BUFEND", "BUFFER"]);
+}
@@ -0,0 +1,199 @@
+//! Each backend is parsed back and has to give the same memory contents.
+use sic::image::{binary, intel_hex, memory, srecord};
+use sic::{assemble, ObjectProgram, Options};
+use std::collections::BTreeMap;
+
+/// Code on both sides of the 64K boundary, which Intel HEX needs an
+/// extended linear address record for.
+const HIGH: &str = "\
+HIGH\tSTART\tFFF8
+FIRST\t+LDA\tDATA
+\tLDA\t#1
+DATA\tWORD\t5
+GAP\tRESB\t3
+\tWORD\t-1
+\tEND\tFIRST
+";
+
+/// Two control sections, loaded one after the other, with an external
+/// reference and relative fields for the M records to fix up.
+const SECTIONS: &str = "\
+MAIN\tSTART\t100
+\tEXTREF\tSUB
+FIRST\t+JSUB\tSUB
+\tRESW\t2
+\tRSUB
+SUB\tCSECT
+\tEXTDEF\tSUB
+\tLDA\t#7
+PTR\tWORD\tPTR
+\t+LDA\tPTR
+\tRSUB
+\tEND\tFIRST
+";
+
+fn programs() -> Vec<ObjectProgram> {
+    [include_str!("../input.asm"), HIGH, SECTIONS]
+        .into_iter()
+        .map(|source| assemble(source, Options::default()).unwrap())
+        .collect()
+}
+
+fn bytes(text: &str) -> Vec<u8> {
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Parses Intel HEX into memory contents and the start address.
+fn parse_intel_hex(text: &str) -> (BTreeMap<u32, u8>, Option<u32>) {
+    let mut memory = BTreeMap::new();
+    let mut upper = 0;
+    let mut start = None;
+    let mut ended = false;
+    for line in text.lines() {
+        assert!(!ended, "data after the EOF record");
+        let record = bytes(line.strip_prefix(':').unwrap());
+        let sum = record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+        assert_eq!(sum, 0, "bad checksum in {}", line);
+        let len = record[0] as usize;
+        let address = u16::from_be_bytes([record[1], record[2]]) as u32;
+        let data = &record[4..4 + len];
+        assert_eq!(record.len(), len + 5);
+        match record[3] {
+            0x00 => {
+                for (i, &byte) in data.iter().enumerate() {
+                    memory.insert((upper << 16) + address + i as u32, byte);
+                }
+            }
+            0x01 => ended = true,
+            0x04 => upper = u16::from_be_bytes([data[0], data[1]]) as u32,
+            0x05 => start = Some(u32::from_be_bytes(data.try_into().unwrap())),
+            kind => panic!("unexpected record type {:02X}", kind),
+        }
+    }
+    assert!(ended, "missing EOF record");
+    (memory, start)
+}
+
+/// Parses S-records into the header, memory contents and start address.
+fn parse_srecord(text: &str) -> (String, BTreeMap<u32, u8>, u32) {
+    let mut header = String::new();
+    let mut memory = BTreeMap::new();
+    let mut start = None;
+    for line in text.lines() {
+        let kind = &line[..2];
+        let record = bytes(&line[2..]);
+        let sum = record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+        assert_eq!(sum, 0xFF, "bad checksum in {}", line);
+        assert_eq!(record[0] as usize, record.len() - 1);
+        let address_len = match kind {
+            "S0" | "S1" | "S9" => 2,
+            "S2" | "S8" => 3,
+            _ => panic!("unexpected record {}", kind),
+        };
+        let address = record[1..1 + address_len]
+            .iter()
+            .fold(0u32, |address, b| address << 8 | *b as u32);
+        let data = &record[1 + address_len..record.len() - 1];
+        match kind {
+            "S0" => header = String::from_utf8(data.to_vec()).unwrap(),
+            "S1" | "S2" => {
+                for (i, &byte) in data.iter().enumerate() {
+                    memory.insert(address + i as u32, byte);
+                }
+            }
+            _ => start = Some(address),
+        }
+    }
+    (header, memory, start.expect("missing termination record"))
+}
+
+#[test]
+fn binary_round_trips_with_the_fill_byte() {
+    for program in programs() {
+        let start = program.sections[0].section.start;
+        for fill in [0x00, 0xFF] {
+            let image = binary(&program, fill);
+            let mut expected = memory(&program);
+            let end = start + image.len() as u32;
+            for address in start..end {
+                expected.entry(address).or_insert(fill);
+            }
+            let parsed: BTreeMap<u32, u8> = image
+                .iter()
+                .enumerate()
+                .map(|(i, &byte)| (start + i as u32, byte))
+                .collect();
+            assert_eq!(parsed, expected);
+        }
+    }
+}
+
+#[test]
+fn intel_hex_round_trips() {
+    for program in programs() {
+        let (parsed, start) = parse_intel_hex(&intel_hex(&program));
+        assert_eq!(parsed, memory(&program));
+        assert_eq!(start, program.sections[0].entry);
+    }
+}
+
+#[test]
+fn srecord_round_trips() {
+    for program in programs() {
+        let (header, parsed, start) = parse_srecord(&srecord(&program));
+        assert_eq!(header, program.sections[0].section.name);
+        assert_eq!(parsed, memory(&program));
+        assert_eq!(Some(start), program.sections[0].entry);
+    }
+}
+
+#[test]
+fn lays_out_memory_like_a_loader() {
+    let high = assemble(HIGH, Options::default()).unwrap();
+    let memory = memory(&high);
+    assert_eq!(memory.keys().next(), Some(&0xFFF8));
+    assert_eq!(memory.keys().last(), Some(&0x10007));
+    assert!(intel_hex(&high).contains(":020000040001F9\n"));
+    assert_eq!(
+        binary(&high, 0xFF)[7..13],
+        [0x00, 0x00, 0x05, 0xFF, 0xFF, 0xFF]
+    );
+
+    // SUB is loaded right after MAIN's 13 bytes.
+    let sections = assemble(SECTIONS, Options::default()).unwrap();
+    let memory = sic::image::memory(&sections);
+    assert_eq!(memory.get(&0x10A), Some(&0x4F));
+    assert_eq!(memory.get(&0x10D), Some(&0x01));
+    assert_eq!(binary(&sections, 0).len(), 26);
+}
+
+#[test]
+fn applies_modification_records() {
+    let field = |memory: &BTreeMap<u32, u8>, address: u32| -> Vec<u8> {
+        (address..address + 4).map(|a| memory[&a]).collect()
+    };
+    for symbolic_modification in [false, true] {
+        let options = Options {
+            symbolic_modification,
+            ..Options::default()
+        };
+        let memory = memory(&assemble(SECTIONS, options).unwrap());
+        // +JSUB SUB jumps to where SUB was loaded.
+        assert_eq!(field(&memory, 0x100), [0x4B, 0x10, 0x01, 0x0D]);
+        // PTR is SUB+3 both as a word and as a format 4 operand.
+        assert_eq!(field(&memory, 0x110)[..3], [0x00, 0x01, 0x10]);
+        assert_eq!(field(&memory, 0x113), [0x03, 0x10, 0x01, 0x10]);
+    }
+}
+
+#[test]
+fn keeps_code_past_the_section_length() {
+    let mut program = assemble(HIGH, Options::default()).unwrap();
+    program.sections[0].section.length = 4;
+    let image = binary(&program, 0xFF);
+    assert_eq!(image.len(), 16);
+    assert_eq!(image[7..10], [0x00, 0x00, 0x05]);
+}
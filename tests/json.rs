@@ -0,0 +1,48 @@
+use sic::json::{json, JSON_VERSION};
+use sic::messages::Lang;
+use sic::{Assembler, Options};
+use std::process::Command;
+
+#[test]
+fn describes_every_line() {
+    let source = "P\tSTART\t0\nFIRST\tLDA\t#5\n\t+STA\tBUF,X\nBUF\tRESB\t1\n\tEND\tFIRST\n";
+    let assembler = Assembler::new(Options::default());
+    let (program, diagnostics) = assembler.run(source);
+    let json = json(source, &program, &diagnostics, Lang::En);
+    assert_eq!(JSON_VERSION, 1);
+    assert!(json.starts_with("{\n  \"version\": 1,\n  \"name\": \"P\",\n  \"start\": 0,\n  \"length\": 8,\n  \"entry\": 0,\n"));
+    assert!(json.contains(r#"{"name": "BUF", "value": 7, "type": "relative", "line": 4}"#));
+    assert!(json.contains(
+        r#"{"line": 2, "address": 0, "source": "FIRST\tLDA\t#5", "label": "FIRST", "mnemonic": "LDA", "operand": "5", "format": 3, "flags": {"n": false, "i": true, "x": false, "b": false, "p": false, "e": false}, "object": "010005"}"#
+    ));
+    assert!(json.contains(
+        r#"{"line": 3, "address": 3, "source": "\t+STA\tBUF,X", "label": null, "mnemonic": "+STA", "operand": "BUF", "format": 4, "flags": {"n": true, "i": true, "x": true, "b": false, "p": false, "e": true}, "object": "0F900007"}"#
+    ));
+    assert!(json.ends_with("  \"diagnostics\": []\n}\n"));
+}
+
+#[test]
+fn is_written_with_the_errors() {
+    let source = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus/errors.asm");
+    let output = Command::new(env!("CARGO_BIN_EXE_sic"))
+        .args(["--format", "json", "--lang", "en", "-o", "-"])
+        .arg(source)
+        .output()
+        .unwrap();
+    let json = String::from_utf8(output.stdout).unwrap();
+    assert!(!output.status.success());
+    assert!(json.contains(
+        r#"{"severity": "error", "line": 3, "column": 2, "id": "invalid-opcode", "message": "LDAX is not a valid opcode"}"#
+    ));
+}
+
+#[test]
+fn lists_the_literal_table() {
+    let source = "P\tSTART\t0\n\tLDA\t=c'EOF'\n\tLTORG\n\tCOMP\t=X'05'\n\tEND\n";
+    let assembler = Assembler::new(Options::default());
+    let (program, diagnostics) = assembler.run(source);
+    let json = json(source, &program, &diagnostics, Lang::En);
+    assert!(json.contains(
+        "\"literals\": [\n        {\"name\": \"=C'EOF'\", \"value\": 3, \"bytes\": \"454F46\", \"line\": 3},\n        {\"name\": \"=X'05'\", \"value\": 9, \"bytes\": \"05\", \"line\": 5}\n      ],"
+    ));
+}
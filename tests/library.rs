@@ -0,0 +1,292 @@
+use sic::diagnostic::{Severity, Span};
+use sic::{assemble, AsmError, Assembler, FileWriter, Options};
+
+#[test]
+fn assembles_in_process() {
+    let program = assemble(include_str!("../input.asm"), Options::default()).unwrap();
+    assert_eq!(program.to_string(), include_str!("../output.txt"));
+    assert!(program.warnings.is_empty());
+    let main = &program.sections[0];
+    assert_eq!(main.section.name, "COPY");
+    assert_eq!(
+        main.section.symbol_table.get("RDREC").unwrap().value,
+        0x203F
+    );
+    assert_eq!(main.entry, Some(0x1000));
+}
+
+#[test]
+fn returns_every_diagnostic() {
+    let assembler = Assembler::new(Options::default());
+    assert!(assembler.optab().is_opcode("LDA").is_some());
+    let diagnostics = assembler
+        .assemble("P\tSTART\t0\n\tLDA\tNOPE\n\tLDAX\t#0\n")
+        .unwrap_err();
+    let errors: Vec<_> = diagnostics
+        .sorted()
+        .into_iter()
+        .map(|d| (d.line, d.severity, &d.error))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (0, Severity::Warning, &AsmError::NoEnd),
+            (
+                2,
+                Severity::Error,
+                &AsmError::UndefinedSymbol("NOPE".into())
+            ),
+            (
+                3,
+                Severity::Error,
+                &AsmError::InvalidOpcode {
+                    opcode: "LDAX".into(),
+                    suggestion: Some("LDA".into()),
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn renders_into_memory() {
+    let program = assemble(include_str!("../input.asm"), Options::default()).unwrap();
+    let mut writer = FileWriter::new(Vec::new());
+    writer.write(program.to_string()).unwrap();
+    let bytes = writer.into_inner().unwrap();
+    assert_eq!(
+        String::from_utf8(bytes).unwrap(),
+        include_str!("../output.txt")
+    );
+}
+
+#[test]
+fn rejects_addresses_beyond_memory() {
+    for source in [
+        "P\tSTART\t0\n\tRESW\t2000000000\n\tEND\n",
+        "P\tSTART\t0\n\tRESB\t4294967295\n\tLDA\t#0\n\tEND\n",
+        "P\tSTART\tFFFFFFFF\n\tLDA\t#0\n\tEND\n",
+        "P\tSTART\tFFFF0\n\tRESB\t16\n\tUSE\tB\n\tRESB\t1\n\tEND\n",
+    ] {
+        let diagnostics = assemble(source, Options::default()).unwrap_err();
+        assert!(
+            diagnostics
+                .sorted()
+                .iter()
+                .any(|d| d.error == AsmError::AddressOutOfRange),
+            "{:?}",
+            source
+        );
+    }
+    assert!(assemble("P\tSTART\tFFFF0\n\tRESB\t16\n\tEND\n", Options::default()).is_ok());
+}
+
+#[test]
+fn directives_are_case_insensitive() {
+    let upper = "P\tSTART\t100\nW\tWORD\t5\nBUF\tRESB\t10\n\tLDA\tW\n\tEND\n";
+    let lower = "P\tstart\t100\nW\tword\t5\nBUF\tresb\t10\n\tlda\tW\n\tend\n";
+    let upper = assemble(upper, Options::default()).unwrap().to_string();
+    assert_eq!(
+        assemble(lower, Options::default()).unwrap().to_string(),
+        upper
+    );
+    assert!(upper.starts_with("HP     000100000010\n"));
+}
+
+/// The errors of a program that fails to assemble, in line order.
+fn errors(source: &str) -> Vec<AsmError> {
+    let diagnostics = assemble(source, Options::default()).unwrap_err();
+    diagnostics
+        .sorted()
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| d.error.clone())
+        .collect()
+}
+
+#[test]
+fn rejects_extended_format_1_and_2() {
+    assert_eq!(
+        errors("P\tSTART\t0\n\t+FIX\n\t+CLEAR\tA\n\tEND\n"),
+        [
+            AsmError::ExtendedFormat("FIX".into()),
+            AsmError::ExtendedFormat("CLEAR".into()),
+        ]
+    );
+}
+
+#[test]
+fn rejects_bad_register_operands() {
+    let source = "P\tSTART\t0\n\tCLEAR\tQ\n\tCOMPR\tA,XX\n\tSHIFTL\tA,0\n\tSHIFTR\tA,17\n\tSVC\t16\n\tCLEAR\tA,X\n\tCOMPR\tA\n\tEND\n";
+    let out_of_range = |text: &str, min, max| AsmError::NumberOutOfRange {
+        text: text.into(),
+        min,
+        max,
+    };
+    assert_eq!(
+        errors(source),
+        [
+            AsmError::UnknownRegister("Q".into()),
+            AsmError::UnknownRegister("XX".into()),
+            out_of_range("0", 1, 16),
+            out_of_range("17", 1, 16),
+            out_of_range("16", 0, 15),
+            AsmError::SingleOperand("CLEAR".into()),
+            AsmError::MissingRegister("COMPR".into()),
+        ]
+    );
+}
+
+#[test]
+fn rejects_bad_indexing() {
+    assert_eq!(
+        errors("P\tSTART\t0\n\tLDA\t#1,X\n\tLDA\t@P,X\n\tLDA\tP,A\n\tEND\n"),
+        [
+            AsmError::IndexedImmediate,
+            AsmError::IndexedImmediate,
+            AsmError::InvalidIndexRegister("A".into()),
+        ]
+    );
+}
+
+#[test]
+fn rejects_bad_end_operands() {
+    assert_eq!(
+        errors("P\tSTART\t0\n\tRSUB\n\tEND\tNOPE\n"),
+        [AsmError::UndefinedSymbol("NOPE".into())]
+    );
+    assert_eq!(
+        errors("P\tSTART\t0\n\tEXTREF\tX\n\tRSUB\n\tEND\tX\n"),
+        [AsmError::EndExternal("X".into())]
+    );
+}
+
+#[test]
+fn constants_are_case_insensitive() {
+    let upper =
+        "P\tSTART\t0\n\tLDA\t=C'EOF'\n\tCOMP\t=X'05'\nB\tBYTE\tC'AB'\n\tBYTE\tX'F1'\n\tEND\n";
+    let lower =
+        "P\tSTART\t0\n\tLDA\t=c'EOF'\n\tCOMP\t=x'05'\nB\tBYTE\tc'AB'\n\tBYTE\tx'f1'\n\tEND\n";
+    assert_eq!(
+        assemble(lower, Options::default()).unwrap().to_string(),
+        assemble(upper, Options::default()).unwrap().to_string()
+    );
+}
+
+#[test]
+fn falls_back_to_base_relative_until_nobase() {
+    let far = |base: &str, nobase: &str| {
+        format!("P\tSTART\t0\n\t+LDB\t#FAR\n\t{base}\n\tLDA\tFAR\n\t{nobase}\n\tLDA\tFAR\n\tRESB\t4096\nFAR\tWORD\t1\n\tEND\n")
+    };
+    let out_of_range = |source: String| -> Vec<(usize, AsmError)> {
+        let diagnostics = assemble(&source, Options::default()).unwrap_err();
+        diagnostics
+            .sorted()
+            .into_iter()
+            .map(|d| (d.line, d.error.clone()))
+            .collect()
+    };
+    let program = assemble(&far("BASE\tFAR", "LDX\t#0"), Options::default()).unwrap();
+    assert!(program.to_string().contains("034000050000034000"));
+    assert_eq!(
+        out_of_range(far("LDX\t#0", "LDX\t#0")),
+        [
+            (4, AsmError::DisplacementOutOfRange),
+            (6, AsmError::DisplacementOutOfRange),
+        ]
+    );
+    assert_eq!(
+        out_of_range(far("BASE\tFAR", "NOBASE")),
+        [(6, AsmError::DisplacementOutOfRange)]
+    );
+    assert_eq!(
+        AsmError::DisplacementOutOfRange.to_string(),
+        "displacement out of range, use +format 4 or BASE"
+    );
+}
+
+#[test]
+fn negative_equ_values_stay_negative() {
+    let source = "P\tSTART\t0\nOFF\tEQU\t-3\n\tLDA\t#OFF+5\n\tWORD\tOFF+5\n\tWORD\tOFF\n\tEND\n";
+    let program = assemble(source, Options::default()).unwrap();
+    assert!(program
+        .to_string()
+        .contains("T00000009010002000002FFFFFD\n"));
+}
+
+#[test]
+fn rejects_pass_1_pairs_across_blocks() {
+    let source = "P\tSTART\t0\nFIRST\tRSUB\n\tUSE\tDATA\nBUF\tRESB\t16\n\tUSE\nLEN\tEQU\tBUF-FIRST\n\tORG\tFIRST+BUF-FIRST\n\tEND\tFIRST\n";
+    let diagnostics = assemble(source, Options::default()).unwrap_err();
+    let errors: Vec<_> = diagnostics
+        .sorted()
+        .into_iter()
+        .map(|d| (d.line, d.span.map(|s| s.column), d.error.clone()))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (
+                6,
+                Some(9),
+                AsmError::RelativeAcrossBlocks("BUF-FIRST".into())
+            ),
+            (
+                7,
+                Some(6),
+                AsmError::RelativeAcrossBlocks("FIRST+BUF-FIRST".into())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn rejects_immediate_and_indirect_outside_format_3_and_4() {
+    assert_eq!(
+        errors("P\tSTART\t0\n\tCLEAR\t#A\n\tSVC\t@3\n\tWORD\t#5\n\tLDA\t#5\n\tEND\n"),
+        [
+            AsmError::AddressingPrefix("CLEAR".into()),
+            AsmError::AddressingPrefix("SVC".into()),
+            AsmError::AddressingPrefix("WORD".into()),
+        ]
+    );
+}
+
+#[test]
+fn rejects_a_second_operand_on_directives() {
+    assert_eq!(
+        errors(
+            "P\tSTART\t0\n\tWORD\t5,X\n\tBASE\tP,X\n\tEXTREF\tA,B\n\tBYTE\tC'A,B'\n\tEND\tP,Q\n"
+        ),
+        [
+            AsmError::SingleOperand("WORD".into()),
+            AsmError::SingleOperand("BASE".into()),
+            AsmError::SingleOperand("END".into()),
+        ]
+    );
+}
+
+#[test]
+fn points_at_undefined_extdef_names() {
+    let diagnostics = assemble(
+        "P\tSTART\t0\n\tEXTDEF\tP, NOPE\n\tRSUB\n\tEND\n",
+        Options::default(),
+    )
+    .unwrap_err();
+    let errors: Vec<_> = diagnostics
+        .sorted()
+        .into_iter()
+        .map(|d| (d.line, d.span, d.error.clone()))
+        .collect();
+    assert_eq!(
+        errors,
+        [(
+            2,
+            Some(Span { column: 12, len: 4 }),
+            AsmError::UndefinedExtdef {
+                name: "NOPE".into(),
+                section: "P".into(),
+            }
+        )]
+    );
+}
@@ -0,0 +1,33 @@
+use sic::messages::Lang;
+use sic::{Assembler, Options};
+
+#[test]
+fn lists_locations_object_code_and_errors() {
+    let source =
+        "LIT\tSTART\t0\n. a comment\n\tLDA\t=C'EOF'\n\tLDAX\t#0\n\tLTORG\n\tJ\tNOPE\n\tEND\n";
+    let assembler = Assembler::new(Options::default());
+    let (program, diagnostics) = assembler.run(source);
+    let listing = assembler.listing(source, &program, &diagnostics, Lang::En);
+    let expected = " Line  Loc   Label    Opcode   Operand            Object code  Comment
+    1  0000  LIT      START    0
+    2        . a comment
+    3  0000           LDA      =C'EOF'            032000
+    4                 LDAX     #0
+       ***** error: LDAX is not a valid opcode
+       ***** help: did you mean LDA?
+    5  0003           LTORG
+       0003  *        =C'EOF'                     454F46
+    6  0006           J        NOPE
+       ***** error: undefined symbol NOPE
+    7  0009           END
+
+Symbol table LIT
+Name     Value   Type
+LIT      000000  relative
+
+Literal table LIT
+Literal      Value   Length
+=C'EOF'      000003  3
+";
+    assert_eq!(listing, expected);
+}
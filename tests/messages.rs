@@ -0,0 +1,210 @@
+//! Every diagnostic has to read properly in each supported language.
+use sic::messages::Lang;
+use sic::AsmError;
+use std::collections::BTreeSet;
+
+const LANGS: [Lang; 2] = [Lang::En, Lang::ZhTw];
+
+/// One value of every `AsmError` variant.
+fn samples() -> Vec<AsmError> {
+    use AsmError::*;
+    let s = |s: &str| s.to_string();
+    vec![
+        MalformedLine,
+        InvalidOpcode {
+            opcode: s("LDAX"),
+            suggestion: Some(s("LDA")),
+        },
+        ExtendedFormat(s("ADDR")),
+        MissingLabel(s("CSECT")),
+        InvalidIndexRegister(s("Y")),
+        IndexedImmediate,
+        AddressingPrefix(s("CLEAR")),
+        LiteralNotAllowed(s("=C'EOF'")),
+        SymbolListExpected(s("EXTREF")),
+        MissingOperand(s("BASE")),
+        UnterminatedConstant(s("C'AB")),
+        EmptyConstant(s("C''")),
+        UnpairedQuote(s("C'A'B'")),
+        InvalidCharacter('\n', s("C'A'")),
+        InvalidHexDigit('G', s("X'GG'")),
+        OddHexDigits(s("X'ABC'")),
+        InvalidByteConstant(s("Z'00'")),
+        InvalidLiteral(s("=")),
+        InvalidNumber {
+            text: s("1G"),
+            radix: 16,
+        },
+        InvalidNumber {
+            text: s("1A"),
+            radix: 10,
+        },
+        NumberTooLarge(s("99999999999")),
+        WordOutOfRange(1 << 24),
+        UnexpectedCharacter('$', s("A$B")),
+        MissingParen(s("(A+B")),
+        IncompleteExpression(s("A+")),
+        IllegalRelative(s("A+B")),
+        RelativeAcrossBlocks(s("BUF-FIRST")),
+        RelativeProduct(s("A*2")),
+        ExternalProduct(s("RDREC*2")),
+        DivisionByZero(s("A/0")),
+        ExpressionOverflow(s("99999999*99999999*99999")),
+        UndefinedSymbol(s("NOPE")),
+        DuplicateSymbol(s("FIRST")),
+        EquExternal(s("RDREC")),
+        OrgForwardReference(Box::new(UndefinedSymbol(s("LATER")))),
+        OrgWithoutPrevious,
+        OrgBeforeStart(s("X'0FFF'")),
+        EndExternal(s("RDREC")),
+        UndefinedExtdef {
+            name: s("BUFFER"),
+            section: s("COPY"),
+        },
+        ExternalNeedsFormat4(s("RDREC")),
+        DisplacementOutOfRange,
+        ValueOutOfRange {
+            value: 5000,
+            format: 3,
+        },
+        MissingRegister(s("CLEAR")),
+        UnknownRegister(s("Q")),
+        MissingNumber(s("SVC")),
+        NumberOutOfRange {
+            text: s("17"),
+            min: 1,
+            max: 16,
+        },
+        SingleOperand(s("CLEAR")),
+        AddressOutOfRange,
+        NoStart,
+        NoEnd,
+        UnusedSymbol(s("RETADR")),
+    ]
+}
+
+/// Stops compiling when a variant is added without a sample above.
+fn covered(error: &AsmError) -> bool {
+    use AsmError::*;
+    match error {
+        MalformedLine
+        | InvalidOpcode { .. }
+        | ExtendedFormat(_)
+        | MissingLabel(_)
+        | InvalidIndexRegister(_)
+        | IndexedImmediate
+        | AddressingPrefix(_)
+        | LiteralNotAllowed(_)
+        | SymbolListExpected(_)
+        | MissingOperand(_)
+        | UnterminatedConstant(_)
+        | EmptyConstant(_)
+        | UnpairedQuote(_)
+        | InvalidCharacter(..)
+        | InvalidHexDigit(..)
+        | OddHexDigits(_)
+        | InvalidByteConstant(_)
+        | InvalidLiteral(_)
+        | InvalidNumber { .. }
+        | NumberTooLarge(_)
+        | WordOutOfRange(_)
+        | UnexpectedCharacter(..)
+        | MissingParen(_)
+        | IncompleteExpression(_)
+        | IllegalRelative(_)
+        | RelativeAcrossBlocks(_)
+        | RelativeProduct(_)
+        | ExternalProduct(_)
+        | DivisionByZero(_)
+        | ExpressionOverflow(_)
+        | UndefinedSymbol(_)
+        | DuplicateSymbol(_)
+        | EquExternal(_)
+        | OrgForwardReference(_)
+        | OrgWithoutPrevious
+        | OrgBeforeStart(_)
+        | EndExternal(_)
+        | UndefinedExtdef { .. }
+        | ExternalNeedsFormat4(_)
+        | DisplacementOutOfRange
+        | ValueOutOfRange { .. }
+        | MissingRegister(_)
+        | UnknownRegister(_)
+        | MissingNumber(_)
+        | NumberOutOfRange { .. }
+        | SingleOperand(_)
+        | AddressOutOfRange
+        | NoStart
+        | NoEnd
+        | UnusedSymbol(_) => true,
+    }
+}
+
+fn has_id(lang: Lang, id: &str) -> bool {
+    lang.catalog().iter().any(|(key, _)| *key == id)
+}
+
+fn placeholders(template: &str) -> BTreeSet<String> {
+    (0..10)
+        .map(|i| format!("{{{}}}", i))
+        .filter(|p| template.contains(p.as_str()))
+        .collect()
+}
+
+#[test]
+fn every_error_has_every_translation() {
+    for error in samples() {
+        assert!(covered(&error));
+        let (id, args) = error.parts();
+        for lang in LANGS {
+            assert!(has_id(lang, id), "{:?} has no {:?} translation", id, lang);
+            let text = lang.message(&error);
+            assert!(
+                placeholders(&text).is_empty(),
+                "unfilled argument in {:?}",
+                text
+            );
+            for arg in args.iter().filter(|_| id != "org-forward-reference") {
+                assert!(text.contains(arg.as_str()), "{:?} lost {:?}", text, arg);
+            }
+        }
+        assert_ne!(Lang::En.message(&error), Lang::ZhTw.message(&error));
+    }
+}
+
+#[test]
+fn catalogs_agree_on_ids_and_arguments() {
+    let ids = |lang: Lang| -> BTreeSet<&str> { lang.catalog().iter().map(|(id, _)| *id).collect() };
+    assert_eq!(ids(Lang::En), ids(Lang::ZhTw));
+    for (id, english) in Lang::En.catalog() {
+        let (_, chinese) = Lang::ZhTw
+            .catalog()
+            .iter()
+            .find(|(key, _)| key == id)
+            .unwrap();
+        assert_eq!(placeholders(english), placeholders(chinese), "{}", id);
+    }
+}
+
+#[test]
+fn nested_errors_are_translated_too() {
+    let error = AsmError::OrgForwardReference(Box::new(AsmError::UndefinedSymbol("LATER".into())));
+    assert_eq!(
+        Lang::ZhTw.message(&error),
+        "ORG 的值必須事先定義：未定義的符號 LATER"
+    );
+    assert_eq!(
+        error.to_string(),
+        "ORG value must be defined beforehand: undefined symbol LATER"
+    );
+}
+
+#[test]
+fn language_tags_and_locales() {
+    assert_eq!(Lang::from_tag("en"), Some(Lang::En));
+    assert_eq!(Lang::from_tag("en_US.UTF-8"), Some(Lang::En));
+    assert_eq!(Lang::from_tag("C"), Some(Lang::En));
+    assert_eq!(Lang::from_tag("zh-TW"), Some(Lang::ZhTw));
+    assert_eq!(Lang::from_tag("zh_TW.UTF-8"), Some(Lang::ZhTw));
+    assert_eq!(Lang::from_tag("fr_FR.UTF-8"), None);
+}
@@ -0,0 +1,76 @@
+use sic::{assemble, AsmError, CrossReference, Options};
+
+const SOURCE: &str = "\
+MAIN\tSTART\t0
+\tEXTDEF\tBUF
+\tEXTREF\tIN,OUT
+FIRST\t+JSUB\tIN
+\tLDA\t#LEN
+SPARE\tRESW\t1
+BUF\tRESB\t16
+LEN\tEQU\t*-BUF
+SUB\tCSECT
+\tEXTREF\tBUF
+LOOP\t+LDA\tBUF
+\tJ\tLOOP
+\tEND\tFIRST
+";
+
+#[test]
+fn lists_definitions_and_references() {
+    let program = assemble(SOURCE, Options::default()).unwrap();
+    let report = CrossReference::new(&program).to_string();
+    assert_eq!(
+        report,
+        "\
+Cross reference MAIN
+Name     Value   Type         Line  References
+BUF      00000A  relative        7  8
+FIRST    000000  relative        4  13
+IN       000000  external        3  4
+LEN      000010  absolute        8  5
+MAIN     000000  relative        1
+OUT      000000  external        3
+SPARE    000007  relative        6
+
+Cross reference SUB
+Name     Value   Type         Line  References
+BUF      000000  external       10  11
+LOOP     000000  relative       11  12
+SUB      000000  relative        9
+"
+    );
+}
+
+#[test]
+fn warns_about_unused_symbols() {
+    let program = assemble(SOURCE, Options::default()).unwrap();
+    let unused: Vec<_> = CrossReference::new(&program)
+        .unused()
+        .into_iter()
+        .map(|d| (d.line, d.error))
+        .collect();
+    assert_eq!(
+        unused,
+        [
+            (3, AsmError::UnusedSymbol("OUT".into())),
+            (6, AsmError::UnusedSymbol("SPARE".into())),
+        ]
+    );
+}
+
+#[test]
+fn lists_literals_after_the_symbols() {
+    let source = "P\tSTART\t0\n\tLDA\t=C'EOF'\n\tLDB\t=c'EOF'\n\tLTORG\n\tCOMP\t=X'05'\n\tEND\n";
+    let program = assemble(source, Options::default()).unwrap();
+    assert_eq!(
+        CrossReference::new(&program).to_string(),
+        "\
+Cross reference P
+Name     Value   Type         Line  References
+P        000000  relative        1
+=C'EOF'  000006  literal         4  2 3
+=X'05'   00000C  literal         6  5
+"
+    );
+}